@@ -0,0 +1,118 @@
+//! Compares testing every projectile against every ship with the `SpatialGrid` broadphase.
+//!
+//! `cargo run --release -p blitz-common --example broadphase [ships]`
+
+use std::{
+    env,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy::{prelude::*, sprite::collide_aabb::collide};
+use blitz_common::{SpatialGrid, PLAYER_SIZE};
+
+/// Same as the server's level and broadphase cell.
+const LEVEL_SIZE: Vec2 = Vec2::new(1920.0, 1080.0);
+const CELL_SIZE: f32 = 128.0;
+const PROJECTILE_SIZE: f32 = 24.0;
+const FRAMES: u32 = 200;
+
+/// Deterministic positions without pulling in a random number crate.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next_position(&mut self) -> Vec2 {
+        let mut next = || {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32
+        };
+        Vec2::new(next(), next()) * LEVEL_SIZE
+    }
+}
+
+fn brute_force(ships: &[Vec2], projectiles: &[Vec2]) -> usize {
+    projectiles
+        .iter()
+        .map(|projectile| {
+            ships
+                .iter()
+                .filter(|ship| {
+                    collide(
+                        ship.extend(0.0),
+                        Vec2::splat(PLAYER_SIZE),
+                        projectile.extend(0.0),
+                        Vec2::splat(PROJECTILE_SIZE),
+                    )
+                    .is_some()
+                })
+                .count()
+        })
+        .sum()
+}
+
+fn broadphase(grid: &mut SpatialGrid, ships: &[Vec2], projectiles: &[Vec2]) -> usize {
+    grid.clear();
+    for (index, ship) in ships.iter().enumerate() {
+        grid.insert(Entity::from_raw(index as u32), *ship, Vec2::splat(PLAYER_SIZE));
+    }
+
+    projectiles
+        .iter()
+        .map(|projectile| {
+            grid.query(*projectile, Vec2::splat(PROJECTILE_SIZE))
+                .into_iter()
+                .filter(|entity| {
+                    let ship = ships[entity.index() as usize];
+                    collide(
+                        ship.extend(0.0),
+                        Vec2::splat(PLAYER_SIZE),
+                        projectile.extend(0.0),
+                        Vec2::splat(PROJECTILE_SIZE),
+                    )
+                    .is_some()
+                })
+                .count()
+        })
+        .sum()
+}
+
+fn time_frames(mut frame: impl FnMut() -> usize) -> (Duration, usize) {
+    let start = Instant::now();
+    let mut hits = 0;
+    for _ in 0..FRAMES {
+        hits = black_box(frame());
+    }
+    (start.elapsed() / FRAMES, hits)
+}
+
+fn main() {
+    let ship_count = env::args()
+        .nth(1)
+        .and_then(|ships| ships.parse().ok())
+        .unwrap_or(64);
+
+    let mut rng = Lcg(7);
+    let ships: Vec<_> = (0..ship_count).map(|_| rng.next_position()).collect();
+    let mut grid = SpatialGrid::new(CELL_SIZE);
+
+    println!("{ship_count} ships, average over {FRAMES} frames");
+    println!("projectiles   brute force   broadphase   speedup");
+
+    for projectile_count in [100, 250, 500, 1000, 2000] {
+        let projectiles: Vec<_> = (0..projectile_count)
+            .map(|_| rng.next_position())
+            .collect();
+
+        let (brute, brute_hits) = time_frames(|| brute_force(&ships, &projectiles));
+        let (grid_time, grid_hits) = time_frames(|| broadphase(&mut grid, &ships, &projectiles));
+        assert_eq!(brute_hits, grid_hits, "broadphase missed a hit");
+
+        println!(
+            "{projectile_count:>11}   {brute:>11.2?}   {grid_time:>10.2?}   {:>6.1}x",
+            brute.as_secs_f64() / grid_time.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use std::{fs, path::Path};
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::WeaponDef;
+
+/// File in the assets directory holding the ability definitions.
+pub const ABILITIES_FILE: &str = "abilities.ron";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum AbilityEffect {
+    /// Flies at `speed` for the ability's duration, towards the movement keys
+    /// or where the ship faces when none are held
+    Dash { speed: f32 },
+    /// Takes up to `absorb` damage in place of the hull until it breaks or the duration ends
+    Shield { absorb: f32 },
+    /// Applies the status effect with that id to the player using it, which decides how long
+    /// it lasts
+    Status { status: String },
+    /// Charges while held and fires `weapon` once released. Damage scales from `min_charge`
+    /// of the weapon's up to all of it after `charge_time` seconds
+    ChargedShot {
+        weapon: WeaponDef,
+        charge_time: f32,
+        min_charge: f32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AbilityDef {
+    /// Sent over the network, must be unique
+    pub id: String,
+    pub name: String,
+    pub effect: AbilityEffect,
+    /// Seconds before it can be used again, counted once its effect is over
+    pub cooldown: f32,
+    /// Seconds the effect lasts, ignored by charged shots and status effects
+    #[serde(default)]
+    pub duration: f32,
+    /// Energy spent when used
+    pub cost: f32,
+}
+
+impl AbilityDef {
+    /// Weapon whose projectiles the ability fires, if any.
+    pub fn weapon(&self) -> Option<&WeaponDef> {
+        match &self.effect {
+            AbilityEffect::ChargedShot { weapon, .. } => Some(weapon),
+            _ => None,
+        }
+    }
+}
+
+/// Every ability, in the order of the player's ability slots.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Resource)]
+pub struct AbilityDefs {
+    pub abilities: Vec<AbilityDef>,
+}
+
+impl AbilityDefs {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        ron::from_str(&contents).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn get(&self, id: &str) -> Option<&AbilityDef> {
+        self.abilities.iter().find(|ability| ability.id == id)
+    }
+}
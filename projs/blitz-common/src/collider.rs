@@ -0,0 +1,445 @@
+use bevy::{math::ivec2, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::CollisionGrid;
+
+/// Shape something is hit with, centered on its transform and turning with it.
+/// Lengths are in pixels, capsules and boxes are laid out along the local Y axis
+/// which is where ships and projectiles point.
+///
+/// Walls push ships back as a square of `PLAYER_SIZE` instead, a shape turning with the ship
+/// could get wedged into a wall just by aiming and wouldn't fit the navigation grid.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Component, PartialEq)]
+pub enum Collider {
+    Circle {
+        radius: f32,
+    },
+    /// Two half circles joined by a rectangle, `length` goes from one end to the other
+    Capsule {
+        length: f32,
+        radius: f32,
+    },
+    Box {
+        size: Vec2,
+    },
+}
+
+impl Default for Collider {
+    fn default() -> Self {
+        Self::Circle { radius: 16.0 }
+    }
+}
+
+impl Collider {
+    /// Size of the axis aligned box around the shape placed at `transform`.
+    pub fn bounds(&self, transform: &Transform) -> Vec2 {
+        match Placed::new(self, transform) {
+            Placed::Segment { from, to, radius } => (to - from).abs() + radius * 2.0,
+            Placed::Box { axes, half, .. } => {
+                ((axes[0] * half.x).abs() + (axes[1] * half.y).abs()) * 2.0
+            }
+        }
+    }
+
+    /// Whether this shape at `transform` overlaps `other` at `other_transform`.
+    pub fn intersects(
+        &self,
+        transform: &Transform,
+        other: &Collider,
+        other_transform: &Transform,
+    ) -> bool {
+        Placed::new(self, transform).intersects(&Placed::new(other, other_transform))
+    }
+
+    /// How far `point` is from the edge of the shape at `transform`, 0 when inside it.
+    pub fn distance_to(&self, transform: &Transform, point: Vec2) -> f32 {
+        match Placed::new(self, transform) {
+            Placed::Segment { from, to, radius } => {
+                (point_segment_distance(point, from, to) - radius).max(0.0)
+            }
+            Placed::Box { center, axes, half } => {
+                let offset = point - center;
+                let local = Vec2::new(offset.dot(axes[0]), offset.dot(axes[1]));
+                (local.abs() - half).max(Vec2::ZERO).length()
+            }
+        }
+    }
+
+    /// Whether the shape at `transform` touches a solid cell of the grid.
+    pub fn overlaps_grid(&self, transform: &Transform, grid: &CollisionGrid) -> bool {
+        if grid.is_empty() {
+            return false;
+        }
+
+        let placed = Placed::new(self, transform);
+        let center = transform.translation.truncate();
+        let half = self.bounds(transform) / 2.0;
+        let min = grid.cell_at(center - half);
+        let max = grid.cell_at(center + half);
+        let cell_half = Vec2::splat(grid.cell_size / 2.0);
+
+        (min.y..=max.y).any(|y| {
+            (min.x..=max.x).any(|x| {
+                let cell = ivec2(x, y);
+                grid.is_solid(cell)
+                    && placed.intersects(&Placed::Box {
+                        center: grid.cell_center(cell),
+                        axes: [Vec2::X, Vec2::Y],
+                        half: cell_half,
+                    })
+            })
+        })
+    }
+}
+
+/// A collider in world space. Circles and capsules are both a segment with a radius,
+/// a circle's segment being a single point.
+enum Placed {
+    Segment {
+        from: Vec2,
+        to: Vec2,
+        radius: f32,
+    },
+    Box {
+        center: Vec2,
+        axes: [Vec2; 2],
+        half: Vec2,
+    },
+}
+
+impl Placed {
+    fn new(collider: &Collider, transform: &Transform) -> Self {
+        let center = transform.translation.truncate();
+        let right = (transform.rotation * Vec3::X)
+            .truncate()
+            .normalize_or_zero();
+        let up = (transform.rotation * Vec3::Y)
+            .truncate()
+            .normalize_or_zero();
+
+        match *collider {
+            Collider::Circle { radius } => Self::Segment {
+                from: center,
+                to: center,
+                radius,
+            },
+            Collider::Capsule { length, radius } => {
+                let reach = up * (length / 2.0 - radius).max(0.0);
+                Self::Segment {
+                    from: center - reach,
+                    to: center + reach,
+                    radius,
+                }
+            }
+            Collider::Box { size } => Self::Box {
+                center,
+                axes: [right, up],
+                half: size / 2.0,
+            },
+        }
+    }
+
+    fn intersects(&self, other: &Placed) -> bool {
+        match (self, other) {
+            (
+                Self::Segment { from, to, radius },
+                Self::Segment {
+                    from: other_from,
+                    to: other_to,
+                    radius: other_radius,
+                },
+            ) => segment_distance(*from, *to, *other_from, *other_to) <= radius + other_radius,
+            (Self::Segment { from, to, radius }, Self::Box { center, axes, half })
+            | (Self::Box { center, axes, half }, Self::Segment { from, to, radius }) => {
+                // In the box's frame it is axis aligned and centered on the origin
+                let local = |point: Vec2| {
+                    let offset = point - *center;
+                    Vec2::new(offset.dot(axes[0]), offset.dot(axes[1]))
+                };
+                segment_box_distance(local(*from), local(*to), *half) <= *radius
+            }
+            (
+                Self::Box { center, axes, half },
+                Self::Box {
+                    center: other_center,
+                    axes: other_axes,
+                    half: other_half,
+                },
+            ) => {
+                // Separating axis test, two boxes only need to be checked along their edges
+                let offset = *other_center - *center;
+                let extent = |axes: &[Vec2; 2], half: Vec2, axis: Vec2| {
+                    (axes[0].dot(axis) * half.x).abs() + (axes[1].dot(axis) * half.y).abs()
+                };
+
+                axes.iter().chain(other_axes.iter()).all(|axis| {
+                    offset.dot(*axis).abs()
+                        <= extent(axes, *half, *axis) + extent(other_axes, *other_half, *axis)
+                })
+            }
+        }
+    }
+}
+
+fn point_segment_distance(point: Vec2, from: Vec2, to: Vec2) -> f32 {
+    let along = to - from;
+    let t = match along.length_squared() {
+        length if length > 0.0 => ((point - from).dot(along) / length).clamp(0.0, 1.0),
+        _ => 0.0,
+    };
+    point.distance(from + along * t)
+}
+
+fn segments_cross(a_from: Vec2, a_to: Vec2, b_from: Vec2, b_to: Vec2) -> bool {
+    let side = |from: Vec2, to: Vec2, point: Vec2| (to - from).perp_dot(point - from);
+
+    side(a_from, a_to, b_from) * side(a_from, a_to, b_to) < 0.0
+        && side(b_from, b_to, a_from) * side(b_from, b_to, a_to) < 0.0
+}
+
+/// Closest distance between two segments. When they don't cross it is always
+/// reached at one of the four end points.
+fn segment_distance(a_from: Vec2, a_to: Vec2, b_from: Vec2, b_to: Vec2) -> f32 {
+    if segments_cross(a_from, a_to, b_from, b_to) {
+        return 0.0;
+    }
+
+    [
+        point_segment_distance(a_from, b_from, b_to),
+        point_segment_distance(a_to, b_from, b_to),
+        point_segment_distance(b_from, a_from, a_to),
+        point_segment_distance(b_to, a_from, a_to),
+    ]
+    .into_iter()
+    .fold(f32::MAX, f32::min)
+}
+
+/// Closest distance between a segment and the box of half size `half` centered on the origin.
+fn segment_box_distance(from: Vec2, to: Vec2, half: Vec2) -> f32 {
+    // Slab test, the segment passing through the box is a hit
+    let delta = to - from;
+    let (mut enter, mut exit) = (0.0f32, 1.0f32);
+    let mut inside = true;
+
+    for axis in 0..2 {
+        if delta[axis].abs() < f32::EPSILON {
+            if from[axis].abs() > half[axis] {
+                inside = false;
+            }
+            continue;
+        }
+
+        let a = (-half[axis] - from[axis]) / delta[axis];
+        let b = (half[axis] - from[axis]) / delta[axis];
+        enter = enter.max(a.min(b));
+        exit = exit.min(a.max(b));
+    }
+
+    if inside && enter <= exit {
+        return 0.0;
+    }
+
+    // Otherwise the closest points are an end of the segment or a corner of the box
+    let point_box_distance = |point: Vec2| (point.abs() - half).max(Vec2::ZERO).length();
+    let corners = [
+        half,
+        -half,
+        Vec2::new(half.x, -half.y),
+        Vec2::new(-half.x, half.y),
+    ];
+
+    corners
+        .into_iter()
+        .map(|corner| point_segment_distance(corner, from, to))
+        .chain([point_box_distance(from), point_box_distance(to)])
+        .fold(f32::MAX, f32::min)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use super::*;
+
+    fn at(x: f32, y: f32) -> Transform {
+        Transform::from_xyz(x, y, 0.0)
+    }
+
+    fn turned(x: f32, y: f32, angle: f32) -> Transform {
+        at(x, y).with_rotation(Quat::from_rotation_z(angle))
+    }
+
+    fn hit(a: Collider, a_at: Transform, b: Collider, b_at: Transform) -> bool {
+        let hit = a.intersects(&a_at, &b, &b_at);
+        assert_eq!(hit, b.intersects(&b_at, &a, &a_at), "order must not matter");
+        hit
+    }
+
+    fn assert_close(a: Vec2, b: Vec2) {
+        assert!(a.abs_diff_eq(b, 1e-3), "{a} != {b}");
+    }
+
+    const CIRCLE: Collider = Collider::Circle { radius: 10.0 };
+    const SQUARE: Collider = Collider::Box {
+        size: Vec2::splat(20.0),
+    };
+    const CAPSULE: Collider = Collider::Capsule {
+        length: 40.0,
+        radius: 5.0,
+    };
+
+    #[test]
+    fn circle_circle() {
+        assert!(hit(CIRCLE, at(0.0, 0.0), CIRCLE, at(19.0, 0.0)));
+        assert!(hit(CIRCLE, at(0.0, 0.0), CIRCLE, at(20.0, 0.0)));
+        assert!(!hit(CIRCLE, at(0.0, 0.0), CIRCLE, at(20.5, 0.0)));
+    }
+
+    #[test]
+    fn capsule_capsule() {
+        // Side by side along their length
+        assert!(hit(CAPSULE, at(0.0, 0.0), CAPSULE, at(9.0, 10.0)));
+        assert!(!hit(CAPSULE, at(0.0, 0.0), CAPSULE, at(11.0, 10.0)));
+        // Crossing each other with all end points apart
+        assert!(hit(
+            CAPSULE,
+            at(0.0, 0.0),
+            CAPSULE,
+            turned(0.0, 0.0, FRAC_PI_2)
+        ));
+        // End to end
+        assert!(hit(CAPSULE, at(0.0, 0.0), CAPSULE, at(0.0, 40.0)));
+        assert!(!hit(CAPSULE, at(0.0, 0.0), CAPSULE, at(0.0, 41.0)));
+    }
+
+    #[test]
+    fn capsule_box() {
+        assert!(hit(CAPSULE, at(14.0, 0.0), SQUARE, at(0.0, 0.0)));
+        assert!(!hit(CAPSULE, at(16.0, 0.0), SQUARE, at(0.0, 0.0)));
+        // Lying across the box, longer than it on both sides
+        assert!(hit(
+            CAPSULE,
+            turned(0.0, 0.0, FRAC_PI_2),
+            SQUARE,
+            at(0.0, 0.0)
+        ));
+        // Past a corner, inside the box's bounds on one axis only
+        assert!(!hit(CAPSULE, at(14.0, 40.0), SQUARE, at(0.0, 0.0)));
+    }
+
+    #[test]
+    fn box_box() {
+        assert!(hit(SQUARE, at(0.0, 0.0), SQUARE, at(15.0, 15.0)));
+        assert!(!hit(SQUARE, at(0.0, 0.0), SQUARE, at(21.0, 0.0)));
+    }
+
+    #[test]
+    fn rotated_boxes() {
+        let diamond = |x, y| turned(x, y, FRAC_PI_4);
+        // A corner of the diamond reaches 10 * sqrt(2) from its center
+        assert!(hit(SQUARE, at(0.0, 0.0), SQUARE, diamond(23.0, 0.0)));
+        assert!(!hit(SQUARE, at(0.0, 0.0), SQUARE, diamond(25.0, 0.0)));
+        // Their bounds overlap, but the diamond's edge keeps them apart
+        assert!(!hit(SQUARE, at(0.0, 0.0), SQUARE, diamond(22.0, 22.0)));
+
+        let plank = Collider::Box {
+            size: Vec2::new(64.0, 8.0),
+        };
+        assert!(hit(
+            plank,
+            turned(0.0, 0.0, FRAC_PI_2),
+            SQUARE,
+            at(0.0, 30.0)
+        ));
+        assert!(!hit(plank, at(0.0, 0.0), SQUARE, at(0.0, 30.0)));
+    }
+
+    #[test]
+    fn segment_through_box() {
+        let half = Vec2::splat(5.0);
+        assert_eq!(
+            segment_box_distance(Vec2::new(-20.0, 0.0), Vec2::new(20.0, 0.0), half),
+            0.0
+        );
+        assert_eq!(
+            segment_box_distance(Vec2::new(-20.0, -20.0), Vec2::new(20.0, 20.0), half),
+            0.0
+        );
+        assert_eq!(
+            segment_box_distance(Vec2::new(-20.0, 8.0), Vec2::new(20.0, 8.0), half),
+            3.0
+        );
+    }
+
+    #[test]
+    fn touching_shapes_intersect() {
+        assert!(hit(CIRCLE, at(20.0, 0.0), SQUARE, at(0.0, 0.0)));
+        assert!(hit(SQUARE, at(0.0, 0.0), SQUARE, at(20.0, 0.0)));
+        assert!(hit(SQUARE, at(0.0, 0.0), SQUARE, at(20.0, 20.0)));
+        assert_eq!(
+            segment_distance(
+                Vec2::ZERO,
+                Vec2::X,
+                Vec2::new(1.0, 0.0),
+                Vec2::new(1.0, 1.0)
+            ),
+            0.0
+        );
+    }
+
+    #[test]
+    fn zero_length_capsule_is_a_circle() {
+        let dot = Collider::Capsule {
+            length: 0.0,
+            radius: 10.0,
+        };
+        assert!(hit(dot, at(0.0, 0.0), CIRCLE, at(20.0, 0.0)));
+        assert!(!hit(dot, at(0.0, 0.0), CIRCLE, at(20.5, 0.0)));
+        assert!(hit(dot, at(0.0, 0.0), dot, at(0.0, 0.0)));
+        assert_close(dot.bounds(&turned(0.0, 0.0, 1.0)), Vec2::splat(20.0));
+    }
+
+    #[test]
+    fn distance_to_edge() {
+        assert_eq!(
+            CIRCLE.distance_to(&at(0.0, 0.0), Vec2::new(25.0, 0.0)),
+            15.0
+        );
+        assert_eq!(CIRCLE.distance_to(&at(0.0, 0.0), Vec2::new(5.0, 0.0)), 0.0);
+        assert_eq!(
+            CAPSULE.distance_to(&at(0.0, 0.0), Vec2::new(0.0, 30.0)),
+            10.0
+        );
+        assert_eq!(
+            SQUARE.distance_to(&at(0.0, 0.0), Vec2::new(13.0, 14.0)),
+            5.0
+        );
+        assert!(
+            (SQUARE.distance_to(&turned(0.0, 0.0, FRAC_PI_4), Vec2::new(20.0, 0.0))
+                - (20.0 - 10.0 * 2.0f32.sqrt()))
+            .abs()
+                < 1e-3
+        );
+    }
+
+    #[test]
+    fn bounds_follow_rotation() {
+        let ship = Collider::Box {
+            size: Vec2::new(64.0, 32.0),
+        };
+        assert_close(ship.bounds(&at(5.0, 5.0)), Vec2::new(64.0, 32.0));
+        assert_close(
+            ship.bounds(&turned(0.0, 0.0, FRAC_PI_2)),
+            Vec2::new(32.0, 64.0),
+        );
+        assert_close(
+            SQUARE.bounds(&turned(0.0, 0.0, FRAC_PI_4)),
+            Vec2::splat(20.0 * 2.0f32.sqrt()),
+        );
+        assert_close(CAPSULE.bounds(&at(0.0, 0.0)), Vec2::new(10.0, 40.0));
+        assert_close(
+            CAPSULE.bounds(&turned(0.0, 0.0, FRAC_PI_2)),
+            Vec2::new(40.0, 10.0),
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::{fs, path::Path};
+
+use bevy::prelude::{Component, Resource};
+use serde::{Deserialize, Serialize};
+
+use crate::{Collider, WeaponDef};
+
+/// File in the assets directory holding the enemy definitions.
+pub const ENEMIES_FILE: &str = "enemies.ron";
+
+/// LDtk entity marking where enemies spawn, its optional `enemy` field restricts the kind.
+pub const ENEMY_SPAWN_ENTITY: &str = "EnemySpawn";
+
+/// A server controlled ship, `kind` is the id of its definition.
+#[derive(Debug, Clone, Component)]
+pub struct Enemy {
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnemyDef {
+    /// Referenced by the map and sent over the network, must be unique
+    pub id: String,
+    pub name: String,
+    pub health: f32,
+    /// Units per second while chasing or fleeing, patrols are slower
+    pub speed: f32,
+    /// Players further away or behind walls go unnoticed
+    pub sight_range: f32,
+    /// Distance the enemy stops closing in and starts shooting from
+    pub attack_range: f32,
+    /// Share of its health below which the enemy runs away
+    pub flee_below: f32,
+    /// Seconds a target that went out of sight is still hunted
+    pub memory: f32,
+    /// Hit by projectiles, walls push back a square of `PLAYER_SIZE` like for players
+    pub collider: Collider,
+    /// Fired at players, its sprite and sound are used by the clients too
+    pub weapon: WeaponDef,
+    /// Relative to the assets directory
+    pub sprite: String,
+    /// Added to the team score when killed in co-op modes
+    #[serde(default = "default_points")]
+    pub points: u32,
+}
+
+fn default_points() -> u32 {
+    10
+}
+
+/// Every enemy kind the server can spawn.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Resource)]
+pub struct EnemyDefs {
+    pub enemies: Vec<EnemyDef>,
+}
+
+impl EnemyDefs {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        ron::from_str(&contents).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn get(&self, id: &str) -> Option<&EnemyDef> {
+        self.enemies.iter().find(|enemy| enemy.id == id)
+    }
+}
@@ -0,0 +1,32 @@
+use bevy::prelude::States;
+use serde::{Deserialize, Serialize};
+
+use crate::Team;
+
+/// Lifecycle of a match, driven by the server and mirrored on the clients.
+#[derive(States, Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum MatchPhase {
+    /// Waiting for enough players, kills don't count yet
+    #[default]
+    Warmup,
+    Countdown,
+    InProgress,
+    /// Showing the winner
+    PostMatch,
+    MapChange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum MatchWinner {
+    Player {
+        id: u64,
+        name: String,
+    },
+    Team(Team),
+    Draw,
+    /// Co-op modes, `waves` being how many the players cleared
+    Survival {
+        waves: u32,
+        survived: bool,
+    },
+}
@@ -1,9 +1,37 @@
+mod ability;
+mod collider;
+mod enemy;
 mod error;
+mod game_mode;
+mod map;
+mod movement;
 mod network;
+mod pickup;
 mod player;
 mod projectile;
+mod score;
+mod ship;
+mod spatial;
+mod status;
+mod team;
+mod weapon;
+mod zone;
 
+pub use ability::*;
+pub use collider::*;
+pub use enemy::*;
 pub use error::*;
+pub use game_mode::*;
+pub use map::*;
+pub use movement::*;
 pub use network::*;
+pub use pickup::*;
 pub use player::*;
 pub use projectile::*;
+pub use score::*;
+pub use ship::*;
+pub use spatial::*;
+pub use status::*;
+pub use team::*;
+pub use weapon::*;
+pub use zone::*;
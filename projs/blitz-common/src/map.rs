@@ -0,0 +1,454 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    path::Path,
+};
+
+use bevy::{
+    math::{ivec2, vec2},
+    prelude::{IVec2, Resource, Vec2},
+};
+use serde::Deserialize;
+
+/// LDtk project in the assets directory holding every level.
+pub const WORLD_FILE: &str = "world.ldtk";
+
+/// Enum tag marking tiles that block movement. Tilesets without any enum tag are solid everywhere.
+pub const SOLID_TILE_TAG: &str = "Solid";
+
+/// Keeps a box that was pushed out of a wall from touching it again due to rounding.
+const CONTACT_OFFSET: f32 = 0.01;
+
+/// The parts of an LDtk project the game cares about, parsed without any rendering.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct MapData {
+    pub levels: Vec<LevelData>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LevelData {
+    pub identifier: String,
+    /// Unique id LDtk gives the level, reported by bevy_ecs_ldtk's level events
+    pub iid: String,
+    /// Hash of the level's JSON so client and server can tell they run the same version
+    pub hash: u64,
+    /// Size in pixels, the level's bottom left corner sits at the world origin
+    pub size: Vec2,
+    pub collision: CollisionGrid,
+    pub entities: Vec<MapEntity>,
+}
+
+/// An entity placed in one of the level's entity layers.
+#[derive(Debug, Clone)]
+pub struct MapEntity {
+    pub identifier: String,
+    /// Center in world coordinates
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Custom fields by identifier, `null` when left empty in the editor
+    pub fields: HashMap<String, serde_json::Value>,
+}
+
+impl MapEntity {
+    pub fn field_str(&self, name: &str) -> Option<&str> {
+        self.fields.get(name)?.as_str()
+    }
+
+    pub fn field_f32(&self, name: &str) -> Option<f32> {
+        self.fields.get(name)?.as_f64().map(|value| value as f32)
+    }
+
+    pub fn field_bool(&self, name: &str) -> Option<bool> {
+        self.fields.get(name)?.as_bool()
+    }
+}
+
+/// Solid cells of a level with row 0 at the bottom, matching world coordinates.
+/// Everything outside the grid is solid so nothing leaves the level.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct CollisionGrid {
+    pub width: i32,
+    pub height: i32,
+    pub cell_size: f32,
+    solid: Vec<bool>,
+}
+
+impl CollisionGrid {
+    pub fn new(width: i32, height: i32, cell_size: f32) -> Self {
+        Self {
+            width,
+            height,
+            cell_size,
+            solid: vec![false; (width.max(0) * height.max(0)) as usize],
+        }
+    }
+
+    /// A grid without cells doesn't block anything, used when no map could be loaded.
+    pub fn is_empty(&self) -> bool {
+        self.solid.is_empty()
+    }
+
+    /// Size in pixels.
+    pub fn size(&self) -> Vec2 {
+        vec2(self.width as f32, self.height as f32) * self.cell_size
+    }
+
+    pub fn contains(&self, cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < self.width && cell.y < self.height
+    }
+
+    pub fn set_solid(&mut self, cell: IVec2) {
+        if self.contains(cell) {
+            let index = (cell.y * self.width + cell.x) as usize;
+            self.solid[index] = true;
+        }
+    }
+
+    pub fn is_solid(&self, cell: IVec2) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        !self.contains(cell) || self.solid[(cell.y * self.width + cell.x) as usize]
+    }
+
+    pub fn cell_at(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn cell_center(&self, cell: IVec2) -> Vec2 {
+        (cell.as_vec2() + 0.5) * self.cell_size
+    }
+
+    pub fn is_solid_at(&self, position: Vec2) -> bool {
+        !self.is_empty() && self.is_solid(self.cell_at(position))
+    }
+
+    /// Whether a box of `size` centered on `center` touches a solid cell.
+    pub fn overlaps(&self, center: Vec2, size: Vec2) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        let half = size / 2.0;
+        let min = self.cell_at(center - half);
+        let max = self.cell_at(center + half - CONTACT_OFFSET);
+
+        (min.y..=max.y).any(|y| (min.x..=max.x).any(|x| self.is_solid(ivec2(x, y))))
+    }
+
+    /// Moves a box by `delta` one axis at a time, stopping flush against walls
+    /// so the other axis keeps sliding along them.
+    pub fn move_and_slide(&self, center: Vec2, size: Vec2, delta: Vec2) -> Vec2 {
+        let mut center = center;
+
+        for axis in [Vec2::X, Vec2::Y] {
+            let step = delta * axis;
+            if step == Vec2::ZERO {
+                continue;
+            }
+
+            let moved = center + step;
+            if !self.overlaps(moved, size) {
+                center = moved;
+                continue;
+            }
+
+            // Snap against the edge of the cell that blocked the leading side
+            let half = size / 2.0;
+            let leading = moved + half * step.signum();
+            let cell_edge = if step.x + step.y > 0.0 {
+                self.cell_at(leading).as_vec2() * self.cell_size - half - CONTACT_OFFSET
+            } else {
+                (self.cell_at(leading).as_vec2() + 1.0) * self.cell_size + half + CONTACT_OFFSET
+            };
+            let snapped = center * (Vec2::ONE - axis) + cell_edge * axis;
+
+            if !self.overlaps(snapped, size) {
+                center = snapped;
+            }
+        }
+
+        center
+    }
+
+    /// Whether nothing solid lies on the straight line between two points.
+    pub fn line_of_sight(&self, from: Vec2, to: Vec2) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        // Sampling at a quarter cell can't step over a whole cell
+        let step = self.cell_size / 4.0;
+        let samples = (from.distance(to) / step).ceil().max(1.0) as i32;
+
+        (0..=samples).all(|i| !self.is_solid_at(from.lerp(to, i as f32 / samples as f32)))
+    }
+
+    /// Center of the free cell closest to `position`, if there is any.
+    pub fn nearest_free(&self, position: Vec2) -> Option<Vec2> {
+        if self.is_empty() {
+            return Some(position);
+        }
+
+        let start = self
+            .cell_at(position)
+            .clamp(IVec2::ZERO, ivec2(self.width - 1, self.height - 1));
+        let mut visited = vec![false; self.solid.len()];
+        let mut queue = VecDeque::from([start]);
+        visited[(start.y * self.width + start.x) as usize] = true;
+
+        while let Some(cell) = queue.pop_front() {
+            if !self.is_solid(cell) {
+                return Some(self.cell_center(cell));
+            }
+
+            for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                let next = cell + offset;
+                if self.contains(next) && !visited[(next.y * self.width + next.x) as usize] {
+                    visited[(next.y * self.width + next.x) as usize] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl MapData {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let project: LdtkProject =
+            serde_json::from_str(&contents).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        let levels = project
+            .levels
+            .into_iter()
+            .map(|value| {
+                let hash = fnv1a(value.to_string().as_bytes());
+                let level: LdtkLevel = serde_json::from_value(value)
+                    .map_err(|e| format!("{}: {e}", path.display()))?;
+
+                Ok(LevelData::from_ldtk(&level, &project.defs, hash))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if levels.is_empty() {
+            return Err(format!("{}: no levels defined", path.display()));
+        }
+
+        Ok(Self { levels })
+    }
+
+    pub fn level(&self, identifier: &str) -> Option<&LevelData> {
+        self.levels
+            .iter()
+            .find(|level| level.identifier == identifier)
+    }
+}
+
+impl LevelData {
+    pub fn entities<'a>(&'a self, identifier: &'a str) -> impl Iterator<Item = &'a MapEntity> {
+        self.entities
+            .iter()
+            .filter(move |entity| entity.identifier == identifier)
+    }
+}
+
+impl LevelData {
+    fn from_ldtk(level: &LdtkLevel, defs: &LdtkDefs, hash: u64) -> Self {
+        let layers = level.layer_instances.as_deref().unwrap_or_default();
+        let cell_size = layers.first().map_or(32, |layer| layer.grid_size);
+
+        let mut collision = CollisionGrid::new(
+            (level.px_wid + cell_size - 1) / cell_size,
+            (level.px_hei + cell_size - 1) / cell_size,
+            cell_size as f32,
+        );
+
+        // Marks the cells covered by a square in LDtk pixels, y pointing down
+        let mut mark = |px: [i32; 2], size: i32| {
+            let min = ivec2(px[0], level.px_hei - px[1] - size) / cell_size;
+            let max = ivec2(px[0] + size - 1, level.px_hei - px[1] - 1) / cell_size;
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    collision.set_solid(ivec2(x, y));
+                }
+            }
+        };
+
+        // A dedicated IntGrid layer wins, otherwise tiles tell what's solid
+        let int_grids: Vec<_> = layers
+            .iter()
+            .filter(|layer| layer.layer_type == "IntGrid")
+            .collect();
+
+        if !int_grids.is_empty() {
+            for layer in int_grids {
+                for (index, value) in layer.int_grid_csv.iter().enumerate() {
+                    if *value != 0 {
+                        let index = index as i32;
+                        let px = [
+                            index % layer.c_wid * layer.grid_size,
+                            index / layer.c_wid * layer.grid_size,
+                        ];
+                        mark(px, layer.grid_size);
+                    }
+                }
+            }
+        } else {
+            for layer in layers {
+                let tileset = layer
+                    .tileset_def_uid
+                    .and_then(|uid| defs.tilesets.iter().find(|tileset| tileset.uid == uid));
+                let solid_tiles = tileset.and_then(|tileset| {
+                    (!tileset.enum_tags.is_empty()).then(|| {
+                        tileset
+                            .enum_tags
+                            .iter()
+                            .filter(|tag| tag.enum_value_id == SOLID_TILE_TAG)
+                            .flat_map(|tag| tag.tile_ids.iter().copied())
+                            .collect::<Vec<_>>()
+                    })
+                });
+
+                for tile in layer.grid_tiles.iter().chain(&layer.auto_layer_tiles) {
+                    let solid = match &solid_tiles {
+                        Some(solid_tiles) => solid_tiles.contains(&tile.t),
+                        None => true,
+                    };
+                    if solid {
+                        mark(tile.px, layer.grid_size);
+                    }
+                }
+            }
+        }
+
+        let entities = layers
+            .iter()
+            .flat_map(|layer| &layer.entity_instances)
+            .map(|entity| {
+                let size = vec2(entity.width as f32, entity.height as f32);
+                let top_left = vec2(entity.px[0] as f32, entity.px[1] as f32)
+                    - vec2(entity.pivot[0], entity.pivot[1]) * size;
+                let center = top_left + size / 2.0;
+
+                MapEntity {
+                    identifier: entity.identifier.clone(),
+                    position: vec2(center.x, level.px_hei as f32 - center.y),
+                    size,
+                    fields: entity
+                        .field_instances
+                        .iter()
+                        .map(|field| (field.identifier.clone(), field.value.clone()))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            identifier: level.identifier.clone(),
+            iid: level.iid.clone(),
+            hash,
+            size: vec2(level.px_wid as f32, level.px_hei as f32),
+            collision,
+            entities,
+        }
+    }
+}
+
+/// 64 bit FNV-1a, stable across platforms and Rust versions unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct LdtkProject {
+    defs: LdtkDefs,
+    /// Kept as raw JSON until hashed
+    levels: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LdtkDefs {
+    tilesets: Vec<LdtkTileset>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkTileset {
+    uid: i32,
+    #[serde(default)]
+    enum_tags: Vec<LdtkEnumTag>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkEnumTag {
+    enum_value_id: String,
+    tile_ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkLevel {
+    identifier: String,
+    iid: String,
+    px_wid: i32,
+    px_hei: i32,
+    layer_instances: Option<Vec<LdtkLayer>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkLayer {
+    #[serde(rename = "__type")]
+    layer_type: String,
+    #[serde(rename = "__cWid")]
+    c_wid: i32,
+    #[serde(rename = "__gridSize")]
+    grid_size: i32,
+    #[serde(rename = "__tilesetDefUid")]
+    tileset_def_uid: Option<i32>,
+    #[serde(default)]
+    int_grid_csv: Vec<i32>,
+    #[serde(default)]
+    grid_tiles: Vec<LdtkTile>,
+    #[serde(default)]
+    auto_layer_tiles: Vec<LdtkTile>,
+    #[serde(default)]
+    entity_instances: Vec<LdtkEntity>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkEntity {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__pivot")]
+    pivot: [f32; 2],
+    width: i32,
+    height: i32,
+    px: [i32; 2],
+    #[serde(default)]
+    field_instances: Vec<LdtkField>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LdtkField {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__value")]
+    value: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct LdtkTile {
+    px: [i32; 2],
+    /// Tile id in the tileset
+    t: i32,
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::{Component, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::PLAYER_MOVE_SPEED;
+
+/// Units per second an entity is moving at, ships report how far they actually got
+/// and projectiles are integrated from it.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Component, PartialEq)]
+pub struct Velocity(pub Vec2);
+
+/// How a ship's velocity follows the movement keys. Speeds are in units per second.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum MovementModel {
+    /// Full speed the moment a key is held, stops dead when released
+    Arcade { speed: f32 },
+    /// Thrust builds up speed and drag slowly takes it away again
+    Thrust {
+        /// Units per second gained every second while thrusting
+        acceleration: f32,
+        /// Share of the velocity lost per second, whether thrusting or not
+        drag: f32,
+        max_speed: f32,
+        /// Thrust multiplier when pushing sideways or backwards from where the ship faces,
+        /// 1.0 is no penalty
+        #[serde(default = "no_strafe_penalty")]
+        strafe_factor: f32,
+    },
+}
+
+fn no_strafe_penalty() -> f32 {
+    1.0
+}
+
+impl Default for MovementModel {
+    fn default() -> Self {
+        Self::Arcade {
+            speed: PLAYER_MOVE_SPEED,
+        }
+    }
+}
+
+impl MovementModel {
+    /// Velocity after `delta_seconds` of holding `input`, one of -1, 0 or 1 per axis,
+    /// for a ship facing `forward`. `boost` scales every speed, e.g. from power-ups.
+    pub fn step(
+        &self,
+        velocity: Vec2,
+        input: Vec2,
+        forward: Vec2,
+        boost: f32,
+        delta_seconds: f32,
+    ) -> Vec2 {
+        // Diagonals are as fast as straight lines
+        let direction = input.normalize_or_zero();
+
+        match *self {
+            Self::Arcade { speed } => direction * speed * boost,
+            Self::Thrust {
+                acceleration,
+                drag,
+                max_speed,
+                strafe_factor,
+            } => {
+                let facing = direction.dot(forward.normalize_or_zero()).max(0.0);
+                let thrust = strafe_factor + (1.0 - strafe_factor) * facing;
+
+                let velocity = velocity + direction * acceleration * boost * thrust * delta_seconds;
+                let velocity = velocity * (1.0 - drag.clamp(0.0, 1.0)).powf(delta_seconds);
+                velocity.clamp_length_max(max_speed * boost)
+            }
+        }
+    }
+}
@@ -1,10 +1,72 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
-use bevy_renet::renet::{ChannelConfig, ReliableChannelConfig, UnreliableChannelConfig};
+use bevy_renet::renet::{
+    ChannelConfig, ReliableChannelConfig, UnreliableChannelConfig, NETCODE_USER_DATA_BYTES,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::{Health, MatchPhase, MatchWinner, Score, StatusStack, Team};
+
 pub const PROTOCOL_ID: u64 = 7;
+pub const MAX_PLAYER_NAME_LENGTH: usize = 32;
+/// Admin tokens are cut down to this many ASCII characters to fit in the user data.
+pub const MAX_ADMIN_TOKEN_LENGTH: usize = 64;
+
+/// Datagram a status query has to start with.
+pub const QUERY_REQUEST: &[u8] = b"BLITZ_QUERY";
+
+/// Sent by the client in the netcode user data when connecting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionData {
+    pub name: String,
+    pub spectator: bool,
+    /// Secret matching one of the server's admin tokens, unlike the name it can't be guessed
+    pub admin_token: Option<String>,
+}
+
+/// Answer to a [`QUERY_REQUEST`] sent to the server's query address.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerStatus {
+    pub players: usize,
+    pub max_players: usize,
+    pub spectators: usize,
+    pub max_spectators: usize,
+    pub queued: usize,
+    pub max_queued: usize,
+}
+
+impl ConnectionData {
+    pub fn new(name: &str, spectator: bool) -> Self {
+        Self {
+            name: name.chars().take(MAX_PLAYER_NAME_LENGTH).collect(),
+            spectator,
+            admin_token: None,
+        }
+    }
+
+    pub fn with_admin_token(mut self, token: &str) -> Self {
+        self.admin_token = Some(
+            token
+                .chars()
+                .filter(char::is_ascii_graphic)
+                .take(MAX_ADMIN_TOKEN_LENGTH)
+                .collect(),
+        );
+        self
+    }
+
+    pub fn to_user_data(&self) -> [u8; NETCODE_USER_DATA_BYTES] {
+        let mut user_data = [0u8; NETCODE_USER_DATA_BYTES];
+        let data = bincode::serialize(self).expect("Failed to Serialize connection data!");
+        user_data[..data.len()].copy_from_slice(&data);
+        user_data
+    }
+
+    pub fn from_user_data(user_data: &[u8; NETCODE_USER_DATA_BYTES]) -> Option<Self> {
+        bincode::deserialize(user_data).ok()
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct NetworkedEntities {
@@ -16,6 +78,7 @@ pub struct NetworkedEntities {
 pub enum ServerMessage {
     PlayerCreate {
         id: u64,
+        name: String,
         entity: Entity,
     },
     PlayerDisconnected {
@@ -25,21 +88,149 @@ pub enum ServerMessage {
         entity: Entity,
         transform: Vec2,
         rotation: Quat,
+        weapon: String,
     },
     DespawnProjectile {
         entity: Entity,
     },
+    /// Something blew up, `radius` is how far it reached.
+    Explosion {
+        position: Vec2,
+        radius: f32,
+    },
+    /// The player died, its ship is hidden until it respawns.
     DespawnPlayer {
         entity: Entity,
     },
     RespawnPlayer {
         entity: Entity,
     },
+    PlayerHealth {
+        entity: Entity,
+        health: Health,
+    },
+    /// Sent right before the server drops a client it refuses to let in.
+    /// `expires` is a unix timestamp in seconds, `None` meaning never.
+    ConnectionRejected {
+        reason: String,
+        expires: Option<u64>,
+    },
+    PlayerScore {
+        id: u64,
+        score: Score,
+    },
+    PlayerTeam {
+        id: u64,
+        team: Team,
+    },
+    /// Round trip time of every player in milliseconds
+    PlayerPings {
+        pings: Vec<(u64, f32)>,
+    },
+    /// `time_left` is in seconds, `None` when the phase has no time limit.
+    MatchState {
+        phase: MatchPhase,
+        time_left: Option<f32>,
+        winner: Option<MatchWinner>,
+    },
+    /// Progress of the survival mode, sent whenever it changes.
+    /// `next_wave` is in seconds and counted down on the client, `None` while a wave is on.
+    SurvivalState {
+        wave: u32,
+        enemies_remaining: u32,
+        score: u32,
+        /// Deaths the team can still afford, `None` when players respawn between waves
+        lives: Option<u32>,
+        next_wave: Option<f32>,
+    },
+    /// Switches to another level of the LDtk world. `hash` must match the client's copy.
+    LoadLevel {
+        identifier: String,
+        hash: u64,
+    },
+    /// Sent to clients waiting for a free player slot whenever the queue moves.
+    JoinQueued {
+        position: usize,
+        length: usize,
+    },
+    /// State of the receiving player's active weapon, sent whenever it changes.
+    /// `heat` cools down on the client at the rate of the weapon definition.
+    WeaponStatus {
+        weapon: String,
+        ammo: Option<u32>,
+        heat: f32,
+        reloading: bool,
+    },
+    /// State of the receiving player's abilities in the order of the definitions, sent whenever
+    /// one is used or becomes ready. Everything counts down or regenerates on the client.
+    AbilityStatus {
+        energy: f32,
+        /// Seconds until each ability can be used again
+        cooldowns: Vec<f32>,
+        /// Seconds left of each ability's effect
+        active: Vec<f32>,
+    },
+    /// Every status effect active on a player, sent whenever one is applied or runs out.
+    /// `remaining` is counted down on the client.
+    PlayerStatus {
+        entity: Entity,
+        effects: Vec<StatusStack>,
+    },
+    /// A pickup became available, either for the first time or after being collected.
+    PickupSpawned {
+        entity: Entity,
+        pickup: String,
+        position: Vec2,
+    },
+    PickupCollected {
+        entity: Entity,
+        player: Entity,
+    },
+    /// The pickup is gone for good, e.g. because the level changed.
+    DespawnPickup {
+        entity: Entity,
+    },
+    SpawnEnemy {
+        entity: Entity,
+        enemy: String,
+        position: Vec2,
+    },
+    /// The enemy died or was removed with its level.
+    DespawnEnemy {
+        entity: Entity,
+    },
+    /// A player set off a zone of the current level, `zone` indexes the level's zones.
+    ZoneTriggered {
+        zone: u16,
+        entity: Entity,
+    },
+    /// Path an agent is about to follow, starting at its position.
+    /// Only sent while the server's navigation debug mode is on.
+    DebugPath {
+        entity: Entity,
+        points: Vec<Vec2>,
+    },
+    /// The server is going away, `reconnect_after` is in seconds when it is only restarting.
+    ServerShutdown {
+        reason: String,
+        reconnect_after: Option<f32>,
+    },
 }
 
-#[derive(Debug, Serialize, Deserialize, Component)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Component)]
 pub enum PlayerCommand {
+    /// Fires the active weapon
     BasicAttack,
+    ChangeTeam(Team),
+    /// Index into the weapon definitions
+    SelectWeapon(u8),
+    NextWeapon,
+    PreviousWeapon,
+    Reload,
+    /// Index into the ability definitions, charged abilities start charging
+    ActivateAbility(u8),
+    /// Lets go of a charged ability, firing it
+    ReleaseAbility(u8),
 }
 
 pub enum ClientChannel {
@@ -62,6 +253,13 @@ impl From<ClientChannel> for u8 {
 }
 
 impl ClientChannel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Input => "input",
+            Self::Command => "command",
+        }
+    }
+
     pub fn channels_config() -> Vec<ChannelConfig> {
         vec![
             ReliableChannelConfig {
@@ -90,6 +288,13 @@ impl From<ServerChannel> for u8 {
 }
 
 impl ServerChannel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ServerMessages => "server_messages",
+            Self::NetworkedEntities => "networked_entities",
+        }
+    }
+
     pub fn channels_config() -> Vec<ChannelConfig> {
         vec![
             UnreliableChannelConfig {
@@ -0,0 +1,65 @@
+use std::{fs, path::Path};
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::Collider;
+
+/// File in the assets directory holding the pickup definitions.
+pub const PICKUPS_FILE: &str = "pickups.ron";
+
+/// LDtk entity marking where a pickup spawns, its `pickup` field names the definition.
+pub const PICKUP_ENTITY: &str = "Pickup";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum PickupEffect {
+    /// Heals up to the maximum health, ignored by players at full health
+    Health { amount: f32 },
+    /// Gives the weapon with that id and fills its magazine
+    Weapon { weapon: String },
+    /// Applies the status effect with that id to the player collecting it
+    Status { status: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PickupDef {
+    /// Referenced by the map and sent over the network, must be unique
+    pub id: String,
+    pub name: String,
+    pub effect: PickupEffect,
+    /// Seconds before a collected pickup comes back
+    pub respawn_time: f32,
+    /// Side of the square sprite
+    pub size: f32,
+    /// Area a ship has to touch to collect it
+    pub collider: Collider,
+    /// Relative to the assets directory
+    pub sprite: String,
+    /// Tint applied to the sprite
+    #[serde(default = "default_color")]
+    pub color: [f32; 3],
+    /// Played when the pickup is collected, relative to the assets directory
+    pub sound: String,
+}
+
+fn default_color() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+/// Every pickup a map can place.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Resource)]
+pub struct PickupDefs {
+    pub pickups: Vec<PickupDef>,
+}
+
+impl PickupDefs {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        ron::from_str(&contents).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn get(&self, id: &str) -> Option<&PickupDef> {
+        self.pickups.iter().find(|pickup| pickup.id == id)
+    }
+}
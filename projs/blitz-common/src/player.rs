@@ -2,6 +2,9 @@ use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
 pub const PLAYER_MOVE_SPEED: f32 = 200.0;
+/// Side of the square a ship pushes against walls, it doesn't turn so aiming can't wedge a
+/// ship into a corner. Projectiles and pickups hit the ship's `Collider` instead.
+pub const PLAYER_SIZE: f32 = 32.0;
 
 #[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, Component, Resource, PartialEq)]
 pub struct PlayerInput {
@@ -21,3 +24,19 @@ pub struct Player {
 pub struct FromPlayer {
     pub entity: Entity,
 }
+
+#[derive(Component, Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
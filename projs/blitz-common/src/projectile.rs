@@ -1,6 +1,23 @@
-use bevy::{prelude::Component, time::Timer};
+use bevy::{
+    prelude::{Component, Entity},
+    time::Timer,
+};
+
+use crate::ExplosionDef;
 
 #[derive(Debug, Component, Default)]
 pub struct Projectile {
     pub duration: Timer,
+    pub damage: f32,
+    /// Walls it can still bounce off, the next one after that destroys it
+    pub bounces: u32,
+    /// Ships it can still fly through, the next one after that stops it
+    pub pierce: u32,
+    /// Status effect applied to the ships it hits
+    pub status: Option<String>,
+    /// Set off where it stops, its damage already scaled like the projectile's. Taken when it
+    /// goes off so a projectile stopped several ways in one frame only explodes once
+    pub explosion: Option<ExplosionDef>,
+    /// Ships it already damaged, a piercing projectile hits each of them once
+    pub hit: Vec<Entity>,
 }
@@ -0,0 +1,23 @@
+use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+#[derive(Component, Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Score {
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+    pub streak: u32,
+    pub best_streak: u32,
+}
+
+impl Score {
+    /// What the scoreboard ranks players by in every mode, assists included. Modes decide who
+    /// wins with their own rule, e.g. kills against the score limit.
+    pub fn points(&self) -> i32 {
+        (self.kills * 2 + self.assists) as i32
+    }
+
+    pub fn kill_death_ratio(&self) -> f32 {
+        self.kills as f32 / self.deaths.max(1) as f32
+    }
+}
@@ -0,0 +1,53 @@
+use std::{fs, path::Path};
+
+use bevy::{math::vec2, prelude::Resource};
+use serde::{Deserialize, Serialize};
+
+use crate::{Collider, MovementModel};
+
+/// File in the assets directory holding the player ship definition.
+pub const SHIP_FILE: &str = "ship.ron";
+
+/// What every player flies.
+#[derive(Debug, Clone, Serialize, Deserialize, Resource, PartialEq)]
+pub struct ShipDef {
+    /// Hit by projectiles and collects pickups, walls still push back a square of `PLAYER_SIZE`
+    pub collider: Collider,
+    #[serde(default)]
+    pub movement: MovementModel,
+    /// Spent by abilities
+    #[serde(default = "default_energy")]
+    pub energy: f32,
+    /// Energy regained per second
+    #[serde(default = "default_energy_regen")]
+    pub energy_regen: f32,
+}
+
+fn default_energy() -> f32 {
+    100.0
+}
+
+fn default_energy_regen() -> f32 {
+    10.0
+}
+
+impl Default for ShipDef {
+    fn default() -> Self {
+        Self {
+            collider: Collider::Box {
+                size: vec2(64.0, 32.0),
+            },
+            movement: MovementModel::default(),
+            energy: default_energy(),
+            energy_regen: default_energy_regen(),
+        }
+    }
+}
+
+impl ShipDef {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        ron::from_str(&contents).map_err(|e| format!("{}: {e}", path.display()))
+    }
+}
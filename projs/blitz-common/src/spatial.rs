@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+/// Uniform grid bucketing entities by every cell their box touches, so overlap queries
+/// only look at what is nearby instead of every entity.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<(Entity, Vec2, Vec2)>>,
+    len: usize,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Number of entities inserted since the last clear.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Empties the grid, keeping its buckets allocated for the next frame.
+    pub fn clear(&mut self) {
+        for entities in self.cells.values_mut() {
+            entities.clear();
+        }
+        self.len = 0;
+    }
+
+    pub fn insert(&mut self, entity: Entity, center: Vec2, size: Vec2) {
+        let (min, max) = self.cell_range(center, size);
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                self.cells
+                    .entry(IVec2::new(x, y))
+                    .or_default()
+                    .push((entity, center, size));
+            }
+        }
+        self.len += 1;
+    }
+
+    /// Entities whose box overlaps the box of `size` centered on `center`, each listed once.
+    pub fn query(&self, center: Vec2, size: Vec2) -> Vec<Entity> {
+        let (min, max) = self.cell_range(center, size);
+        let mut found = Vec::new();
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let Some(entities) = self.cells.get(&IVec2::new(x, y)) else {
+                    continue;
+                };
+
+                for (entity, other_center, other_size) in entities {
+                    let touching = ((*other_center - center).abs() * 2.0)
+                        .cmplt(*other_size + size)
+                        .all();
+                    if touching && !found.contains(entity) {
+                        found.push(*entity);
+                    }
+                }
+            }
+        }
+
+        found
+    }
+
+    fn cell_range(&self, center: Vec2, size: Vec2) -> (IVec2, IVec2) {
+        let half = size / 2.0;
+        let min = ((center - half) / self.cell_size).floor().as_ivec2();
+        let max = ((center + half) / self.cell_size).floor().as_ivec2();
+        (min, max)
+    }
+}
@@ -0,0 +1,75 @@
+use std::{fs, path::Path};
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+/// File in the assets directory holding the status effect definitions.
+pub const STATUS_EFFECTS_FILE: &str = "status_effects.ron";
+
+/// What a status effect does to the player carrying it, once per stack.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum StatusModifier {
+    /// Multiplies the movement speed
+    Speed { multiplier: f32 },
+    /// Multiplies the damage of every projectile fired
+    Damage { multiplier: f32 },
+    /// Multiplies the shots per second of every weapon
+    FireRate { multiplier: f32 },
+    /// Multiplies the damage taken, below 1.0 is armor and above is a weakness
+    IncomingDamage { multiplier: f32 },
+    /// Deals damage every second, credited to whoever applied it
+    DamageOverTime { per_second: f32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StatusDef {
+    /// Referenced by pickups, abilities, weapons and hazards and sent over the network,
+    /// must be unique
+    pub id: String,
+    pub name: String,
+    pub modifier: StatusModifier,
+    /// Seconds every stack lasts
+    pub duration: f32,
+    /// Applying it again adds a stack up to this many, after that the oldest one starts over
+    #[serde(default = "default_max_stacks")]
+    pub max_stacks: u32,
+    /// Tint of the affected ship and its icon
+    #[serde(default = "default_color")]
+    pub color: [f32; 3],
+    /// Shown above the affected ship, relative to the assets directory
+    pub icon: String,
+}
+
+fn default_max_stacks() -> u32 {
+    1
+}
+
+fn default_color() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+/// Every status effect that can be applied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Resource)]
+pub struct StatusDefs {
+    pub effects: Vec<StatusDef>,
+}
+
+impl StatusDefs {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        ron::from_str(&contents).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn get(&self, id: &str) -> Option<&StatusDef> {
+        self.effects.iter().find(|effect| effect.id == id)
+    }
+}
+
+/// A status effect active on a player as sent to the clients.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StatusStack {
+    pub id: String,
+    pub stacks: u32,
+    /// Seconds until the last stack runs out
+    pub remaining: f32,
+}
@@ -0,0 +1,47 @@
+use std::{fmt, str::FromStr};
+
+use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Component, Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub enum Team {
+    A,
+    B,
+}
+
+impl Team {
+    pub const ALL: [Team; 2] = [Team::A, Team::B];
+
+    pub fn opponent(&self) -> Self {
+        match self {
+            Team::A => Team::B,
+            Team::B => Team::A,
+        }
+    }
+}
+
+impl FromStr for Team {
+    type Err = String;
+
+    /// Accepts `A`, `Team A`, `Team_A` and `TeamA`, in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_uppercase().replace([' ', '_'], "");
+
+        match normalized.strip_prefix("TEAM").unwrap_or(&normalized) {
+            "A" => Ok(Team::A),
+            "B" => Ok(Team::B),
+            _ => Err(format!("Unknown team {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Team {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Team::A => write!(f, "Team A"),
+            Team::B => write!(f, "Team B"),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::{fs, path::Path};
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::Collider;
+
+/// File in the assets directory holding the weapon definitions.
+pub const WEAPONS_FILE: &str = "weapons.ron";
+
+/// What limits how long a weapon can keep firing.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum WeaponSupply {
+    #[default]
+    Unlimited,
+    /// Reloads the whole magazine once it is empty or on request
+    Ammo { capacity: u32, reload_time: f32 },
+    /// Every shot adds heat, at 1.0 the weapon overheats and can't fire until it has fully cooled
+    Heat { per_shot: f32, cooling: f32 },
+}
+
+/// Blast set off where a projectile stops, be it on a ship, a wall or at the end of its lifetime.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ExplosionDef {
+    pub radius: f32,
+    /// Damage at the center, falling off to nothing at the edge
+    pub damage: f32,
+    /// Speed in pixels per second ships at the center are pushed away with, falls off like damage
+    pub knockback: f32,
+    /// Walls between the center and a ship shelter it
+    #[serde(default)]
+    pub blocked_by_walls: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WeaponDef {
+    /// Sent over the network, must be unique
+    pub id: String,
+    pub name: String,
+    /// Shots per second
+    pub fire_rate: f32,
+    pub projectile_speed: f32,
+    /// Seconds before a projectile that hit nothing disappears
+    pub lifetime: f32,
+    /// Damage of a single pellet
+    pub damage: f32,
+    /// Cone in degrees the pellets are scattered in
+    pub spread: f32,
+    pub pellets: u32,
+    /// Shape of every projectile, lined up with its flight direction
+    pub collider: Collider,
+    /// Share of the shooter's velocity added to its projectiles
+    #[serde(default)]
+    pub inherit_velocity: f32,
+    /// Walls a projectile bounces off before the next one destroys it
+    #[serde(default)]
+    pub bounces: u32,
+    /// Ships a projectile flies through before the next one stops it
+    #[serde(default)]
+    pub pierce: u32,
+    /// Status effect applied to every ship a projectile hits
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Makes projectiles explode once they stop
+    #[serde(default)]
+    pub explosion: Option<ExplosionDef>,
+    #[serde(default)]
+    pub supply: WeaponSupply,
+    /// Left out of the starting loadout, only handed out by pickups
+    #[serde(default)]
+    pub pickup_only: bool,
+    /// Projectile sprite, relative to the assets directory
+    pub sprite: String,
+    /// Played when the weapon fires, relative to the assets directory
+    pub sound: String,
+}
+
+impl WeaponDef {
+    /// Seconds between two shots.
+    pub fn cooldown(&self) -> f32 {
+        1.0 / self.fire_rate.max(f32::EPSILON)
+    }
+}
+
+impl Default for WeaponDef {
+    fn default() -> Self {
+        Self {
+            id: "blaster".to_string(),
+            name: "Blaster".to_string(),
+            fire_rate: 4.0,
+            projectile_speed: 400.0,
+            lifetime: 1.5,
+            damage: 25.0,
+            spread: 0.0,
+            pellets: 1,
+            collider: Collider::Capsule {
+                length: 54.0,
+                radius: 4.5,
+            },
+            inherit_velocity: 0.0,
+            bounces: 0,
+            pierce: 0,
+            status: None,
+            explosion: None,
+            supply: WeaponSupply::Unlimited,
+            pickup_only: false,
+            sprite: "laser_a_01.png".to_string(),
+            sound: "player_laser.ogg".to_string(),
+        }
+    }
+}
+
+/// Every weapon, in the order of the player's weapon slots.
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+pub struct WeaponDefs {
+    pub weapons: Vec<WeaponDef>,
+}
+
+impl Default for WeaponDefs {
+    fn default() -> Self {
+        Self {
+            weapons: vec![WeaponDef::default()],
+        }
+    }
+}
+
+impl WeaponDefs {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let defs: Self =
+            ron::from_str(&contents).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        if defs.weapons.is_empty() {
+            return Err(format!("{}: no weapons defined", path.display()));
+        }
+
+        // Values the server would divide by or sample a range from when firing
+        for weapon in &defs.weapons {
+            let problem = if !weapon.fire_rate.is_finite() || weapon.fire_rate <= 0.0 {
+                "fire rate must be above 0"
+            } else if !weapon.spread.is_finite() || weapon.spread < 0.0 {
+                "spread can't be negative"
+            } else if weapon.pellets == 0 {
+                "needs at least one pellet"
+            } else {
+                continue;
+            };
+            return Err(format!(
+                "{}: weapon {}: {problem}",
+                path.display(),
+                weapon.id
+            ));
+        }
+
+        Ok(defs)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&WeaponDef> {
+        self.weapons.iter().find(|weapon| weapon.id == id)
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::{Component, Vec2};
+
+use crate::{LevelData, MapEntity};
+
+/// LDtk entity covering an area with a gameplay effect, its `kind` field picks which one.
+pub const ZONE_ENTITY: &str = "Zone";
+
+/// Status effect slow fields apply when their `status` field is left empty.
+pub const SLOW_FIELD_STATUS: &str = "slowed";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ZoneKind {
+    /// Hurts ships inside by `per_second`, e.g. lava or radiation
+    Damage { per_second: f32 },
+    /// Keeps applying the status effect with that id to ships inside
+    Slow { status: String },
+    /// Launches ships and projectiles entering it, `impulse` is in pixels per second
+    BoostPad { impulse: Vec2 },
+    /// Sends whatever enters it to the other teleporter with the same `link`
+    Teleporter { link: String },
+}
+
+/// An area placed on a level as a [`ZONE_ENTITY`].
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct Zone {
+    pub kind: ZoneKind,
+    /// Center in world coordinates
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+impl Zone {
+    /// Reads the fields of a zone entity:
+    /// - `kind`: `Damage`, `Slow`, `BoostPad` or `Teleporter`
+    /// - `damage`: per second, for damage zones
+    /// - `status`: optional for slow fields, defaults to [`SLOW_FIELD_STATUS`]
+    /// - `impulse` and `angle`: launch speed of boost pads and its direction in degrees
+    ///   clockwise from up
+    /// - `link`: name shared by the two teleporters of a pair
+    pub fn from_entity(entity: &MapEntity) -> Result<Self, String> {
+        let kind = match entity.field_str("kind") {
+            Some("Damage") => ZoneKind::Damage {
+                per_second: entity
+                    .field_f32("damage")
+                    .ok_or("Damage zone without a damage field")?,
+            },
+            Some("Slow") => ZoneKind::Slow {
+                status: entity
+                    .field_str("status")
+                    .unwrap_or(SLOW_FIELD_STATUS)
+                    .to_string(),
+            },
+            Some("BoostPad") => {
+                let impulse = entity
+                    .field_f32("impulse")
+                    .ok_or("Boost pad without an impulse field")?;
+                let angle = entity.field_f32("angle").unwrap_or(0.0).to_radians();
+                ZoneKind::BoostPad {
+                    impulse: Vec2::new(angle.sin(), angle.cos()) * impulse,
+                }
+            }
+            Some("Teleporter") => ZoneKind::Teleporter {
+                link: entity
+                    .field_str("link")
+                    .ok_or("Teleporter without a link field")?
+                    .to_string(),
+            },
+            Some(kind) => return Err(format!("Unknown zone kind {kind}")),
+            None => return Err("Zone without a kind field".to_string()),
+        };
+
+        Ok(Self {
+            kind,
+            position: entity.position,
+            size: entity.size,
+        })
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        (point - self.position).abs().cmple(self.size / 2.0).all()
+    }
+}
+
+impl LevelData {
+    /// Every zone entity of the level in map order, the index is how the network refers to them.
+    pub fn zones(&self) -> impl Iterator<Item = Result<Zone, String>> + '_ {
+        self.entities(ZONE_ENTITY).map(Zone::from_entity)
+    }
+
+    /// Index of the teleporter the one at `index` sends to, the other zone sharing its link.
+    pub fn teleporter_destination(&self, index: usize) -> Option<usize> {
+        let zones: Vec<_> = self.zones().collect();
+        let Ok(Zone {
+            kind: ZoneKind::Teleporter { link },
+            ..
+        }) = zones.get(index)?
+        else {
+            return None;
+        };
+
+        (0..zones.len()).find(|&other| {
+            other != index
+                && matches!(
+                    &zones[other],
+                    Ok(Zone { kind: ZoneKind::Teleporter { link: other_link }, .. })
+                        if other_link == link
+                )
+        })
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use blitz_common::{Collider, ServerMessage};
+
+use crate::{menu::ClientState, networking::in_game};
+
+/// Toggles the paths streamed by the server.
+const TOGGLE_KEY: KeyCode = KeyCode::F3;
+/// Toggles the shapes the server hits things with.
+const COLLIDERS_TOGGLE_KEY: KeyCode = KeyCode::F4;
+const PATH_WIDTH: f32 = 2.0;
+const PATH_POINT_SIZE: f32 = 6.0;
+/// Above the level and the ships
+const PATH_Z: f32 = 10.0;
+/// Just below the paths
+const COLLIDER_Z: f32 = 9.0;
+
+/// Sprites drawing the last path the server sent for each agent.
+#[derive(Debug, Resource)]
+struct DebugPaths {
+    visible: bool,
+    paths: HashMap<Entity, Vec<Entity>>,
+}
+
+impl Default for DebugPaths {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            paths: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct PathSprite;
+
+/// Draws every `Collider` while visible, outlines are only created once it is turned on.
+#[derive(Debug, Resource)]
+struct ColliderOverlay {
+    visible: bool,
+    material: Handle<ColorMaterial>,
+    /// Entities whose collider is being drawn
+    outlined: HashSet<Entity>,
+}
+
+impl FromWorld for ColliderOverlay {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+
+        Self {
+            visible: false,
+            outlined: HashSet::new(),
+            material: materials.add(ColorMaterial::from(Color::rgba(0.2, 1.0, 0.4, 0.35))),
+        }
+    }
+}
+
+/// One piece of the shape of `target`, placed at `offset` in the target's frame.
+#[derive(Component)]
+struct ColliderOutline {
+    target: Entity,
+    offset: Vec2,
+}
+
+pub struct ClientDebugPlugin;
+impl Plugin for ClientDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebugPaths>();
+        app.init_resource::<ColliderOverlay>();
+
+        app.add_system(draw_paths.run_if(in_game()));
+        app.add_system(toggle_paths);
+        app.add_system(clear_paths.in_schedule(OnExit(ClientState::InGame)));
+
+        app.add_systems((toggle_colliders, outline_colliders, follow_colliders).chain());
+    }
+}
+
+fn draw_paths(
+    mut commands: Commands,
+    mut server_messages: EventReader<ServerMessage>,
+    mut debug_paths: ResMut<DebugPaths>,
+) {
+    for message in server_messages.iter() {
+        let (agent, points) = match message {
+            ServerMessage::DebugPath { entity, points } => (*entity, points.as_slice()),
+            // The agent is gone, so is its path
+            ServerMessage::DespawnEnemy { entity } => (*entity, [].as_slice()),
+            _ => continue,
+        };
+
+        if let Some(sprites) = debug_paths.paths.remove(&agent) {
+            for sprite in sprites {
+                commands.entity(sprite).despawn();
+            }
+        }
+
+        if points.is_empty() {
+            continue;
+        }
+
+        let visibility = match debug_paths.visible {
+            true => Visibility::Inherited,
+            false => Visibility::Hidden,
+        };
+        let color = Color::rgba(1.0, 0.9, 0.2, 0.8);
+        let mut sprites = Vec::new();
+
+        for segment in points.windows(2) {
+            let (from, to) = (segment[0], segment[1]);
+            let delta = to - from;
+
+            let sprite = commands
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(Vec2::new(delta.length(), PATH_WIDTH)),
+                        ..Default::default()
+                    },
+                    transform: Transform {
+                        translation: from.lerp(to, 0.5).extend(PATH_Z),
+                        rotation: Quat::from_rotation_z(delta.y.atan2(delta.x)),
+                        ..Default::default()
+                    },
+                    visibility,
+                    ..Default::default()
+                })
+                .insert(PathSprite)
+                .id();
+            sprites.push(sprite);
+        }
+
+        for point in points {
+            let sprite = commands
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(Vec2::splat(PATH_POINT_SIZE)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(point.extend(PATH_Z)),
+                    visibility,
+                    ..Default::default()
+                })
+                .insert(PathSprite)
+                .id();
+            sprites.push(sprite);
+        }
+
+        debug_paths.paths.insert(agent, sprites);
+    }
+}
+
+fn toggle_paths(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut debug_paths: ResMut<DebugPaths>,
+    mut sprites: Query<&mut Visibility, With<PathSprite>>,
+) {
+    if !keyboard_input.just_pressed(TOGGLE_KEY) {
+        return;
+    }
+
+    debug_paths.visible = !debug_paths.visible;
+    for mut visibility in sprites.iter_mut() {
+        *visibility = match debug_paths.visible {
+            true => Visibility::Inherited,
+            false => Visibility::Hidden,
+        };
+    }
+}
+
+fn clear_paths(mut commands: Commands, mut debug_paths: ResMut<DebugPaths>) {
+    for sprite in debug_paths.paths.drain().flat_map(|(_, sprites)| sprites) {
+        commands.entity(sprite).despawn();
+    }
+}
+
+fn toggle_colliders(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut overlay: ResMut<ColliderOverlay>,
+    outlines: Query<Entity, With<ColliderOutline>>,
+) {
+    if !keyboard_input.just_pressed(COLLIDERS_TOGGLE_KEY) {
+        return;
+    }
+
+    overlay.visible = !overlay.visible;
+    if overlay.visible {
+        return;
+    }
+
+    for outline in outlines.iter() {
+        commands.entity(outline).despawn();
+    }
+    overlay.outlined.clear();
+}
+
+fn outline_colliders(
+    mut commands: Commands,
+    mut overlay: ResMut<ColliderOverlay>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    colliders: Query<(Entity, &Collider)>,
+) {
+    if !overlay.visible {
+        return;
+    }
+
+    for (target, collider) in colliders.iter() {
+        if !overlay.outlined.insert(target) {
+            continue;
+        }
+
+        // Capsules are a rectangle with a circle at each end
+        let pieces: Vec<(Mesh, Vec2)> = match *collider {
+            Collider::Circle { radius } => vec![(shape::Circle::new(radius).into(), Vec2::ZERO)],
+            Collider::Capsule { length, radius } => {
+                let reach = (length / 2.0 - radius).max(0.0);
+                vec![
+                    (
+                        shape::Quad::new(Vec2::new(radius * 2.0, reach * 2.0)).into(),
+                        Vec2::ZERO,
+                    ),
+                    (shape::Circle::new(radius).into(), Vec2::Y * reach),
+                    (shape::Circle::new(radius).into(), Vec2::NEG_Y * reach),
+                ]
+            }
+            Collider::Box { size } => vec![(shape::Quad::new(size).into(), Vec2::ZERO)],
+        };
+
+        for (mesh, offset) in pieces {
+            commands
+                .spawn(MaterialMesh2dBundle {
+                    mesh: Mesh2dHandle(meshes.add(mesh)),
+                    material: overlay.material.clone(),
+                    // Placed by `follow_colliders` before it is drawn
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                })
+                .insert(ColliderOutline { target, offset });
+        }
+    }
+}
+
+/// Keeps the outlines on their target, ignoring its scale like the server does.
+fn follow_colliders(
+    mut commands: Commands,
+    mut overlay: ResMut<ColliderOverlay>,
+    mut outlines: Query<(Entity, &ColliderOutline, &mut Transform, &mut Visibility)>,
+    targets: Query<(&Transform, &Visibility), Without<ColliderOutline>>,
+) {
+    for (entity, outline, mut transform, mut visibility) in outlines.iter_mut() {
+        let Ok((target_transform, target_visibility)) = targets.get(outline.target) else {
+            commands.entity(entity).despawn();
+            overlay.outlined.remove(&outline.target);
+            continue;
+        };
+
+        let offset = target_transform.rotation * outline.offset.extend(0.0);
+        transform.translation = (target_transform.translation + offset)
+            .truncate()
+            .extend(COLLIDER_Z);
+        transform.rotation = target_transform.rotation;
+        *visibility = match target_visibility {
+            Visibility::Hidden => Visibility::Hidden,
+            _ => Visibility::Inherited,
+        };
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::{math::vec3, prelude::*};
+use blitz_common::ServerMessage;
+
+use crate::{
+    networking::{in_game, resources::NetworkMapping},
+    resources::{Colliders, ExplosionToSpawn, Textures},
+};
+
+#[derive(Component)]
+pub struct EnemyEntity;
+
+pub struct ClientEnemiesPlugin;
+impl Plugin for ClientEnemiesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(sync_enemies.run_if(in_game()));
+    }
+}
+
+fn sync_enemies(
+    mut commands: Commands,
+    mut server_messages: EventReader<ServerMessage>,
+    mut network_mapping: ResMut<NetworkMapping>,
+    enemies: Query<&Transform, With<EnemyEntity>>,
+    textures: Res<Textures>,
+    colliders: Res<Colliders>,
+) {
+    for message in server_messages.iter() {
+        match message {
+            ServerMessage::SpawnEnemy {
+                entity,
+                enemy,
+                position,
+            } => {
+                debug!(network_id = ?entity, %enemy, "Spawn enemy");
+
+                let texture = textures.enemies.get(enemy).unwrap_or(&textures.player);
+                let mut client_entity = commands.spawn(SpriteBundle {
+                    texture: texture.clone(),
+                    transform: Transform {
+                        translation: position.extend(0.0),
+                        scale: vec3(0.5, 0.5, 1.0),
+                        ..Default::default()
+                    },
+                    sprite: Sprite {
+                        color: Color::rgb(3.0, 1.5, 1.5),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                });
+                client_entity.insert(EnemyEntity);
+                if let Some(collider) = colliders.enemies.get(enemy) {
+                    client_entity.insert(*collider);
+                }
+
+                network_mapping.0.insert(*entity, client_entity.id());
+            }
+            ServerMessage::DespawnEnemy { entity } => {
+                debug!(network_id = ?entity, "Despawn enemy");
+
+                let Some(client_entity) = network_mapping.0.remove(entity) else {
+                    continue;
+                };
+
+                if let Ok(transform) = enemies.get(client_entity) {
+                    commands
+                        .spawn_empty()
+                        .insert(ExplosionToSpawn(transform.translation));
+                }
+                commands.entity(client_entity).despawn();
+            }
+            _ => {}
+        }
+    }
+}
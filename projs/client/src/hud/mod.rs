@@ -0,0 +1,328 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{egui, EguiContexts};
+use blitz_common::{
+    AbilityDefs, Health, MatchPhase, MatchWinner, ShipDef, StatusDefs, WeaponDefs, WeaponSupply,
+};
+
+use crate::{
+    menu::ClientState,
+    networking::resources::{
+        AbilityState, ActiveWeapon, ClientLobby, ControlledPlayer, MatchStatus, SurvivalStatus,
+    },
+    player::ABILITY_KEYS,
+    status::StatusEffects,
+};
+
+pub struct ClientHudPlugin;
+impl Plugin for ClientHudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                health_ui,
+                status_ui,
+                weapon_ui,
+                ability_ui,
+                scoreboard_ui,
+                match_ui,
+                survival_ui,
+            )
+                .in_set(OnUpdate(ClientState::InGame)),
+        );
+    }
+}
+
+fn health_ui(
+    mut contexts: EguiContexts,
+    player_query: Query<&Health, With<ControlledPlayer>>,
+) {
+    let Ok(health) = player_query.get_single() else {
+        return;
+    };
+
+    egui::Area::new("health")
+        .anchor(egui::Align2::LEFT_BOTTOM, [16.0, -16.0])
+        .show(contexts.ctx_mut(), |ui| {
+            let text = match health.is_dead() {
+                true => "Destroyed, respawning...".to_string(),
+                false => format!("Hull {:.0}/{:.0}", health.current, health.max),
+            };
+
+            ui.add(
+                egui::ProgressBar::new(health.current / health.max)
+                    .desired_width(200.0)
+                    .text(text),
+            );
+        });
+}
+
+fn status_ui(
+    mut contexts: EguiContexts,
+    player_query: Query<&StatusEffects, With<ControlledPlayer>>,
+    defs: Res<StatusDefs>,
+) {
+    let Ok(effects) = player_query.get_single() else {
+        return;
+    };
+
+    if effects.0.is_empty() {
+        return;
+    }
+
+    egui::Area::new("status")
+        .anchor(egui::Align2::LEFT_BOTTOM, [16.0, -48.0])
+        .show(contexts.ctx_mut(), |ui| {
+            for effect in &effects.0 {
+                let Some(def) = defs.get(&effect.id) else {
+                    continue;
+                };
+
+                let [r, g, b] = def.color.map(|channel| (channel * 255.0) as u8);
+                let text = match effect.stacks {
+                    1 => format!("{} {:.1}s", def.name, effect.remaining),
+                    stacks => format!("{} x{stacks} {:.1}s", def.name, effect.remaining),
+                };
+                ui.colored_label(egui::Color32::from_rgb(r, g, b), text);
+            }
+        });
+}
+
+fn weapon_ui(
+    mut contexts: EguiContexts,
+    mut active_weapon: ResMut<ActiveWeapon>,
+    weapon_defs: Res<WeaponDefs>,
+    time: Res<Time>,
+) {
+    let Some(def) = active_weapon
+        .weapon
+        .as_deref()
+        .and_then(|weapon| weapon_defs.get(weapon))
+    else {
+        return;
+    };
+
+    let status = match def.supply {
+        WeaponSupply::Unlimited => None,
+        WeaponSupply::Ammo { capacity, .. } => {
+            let ammo = active_weapon.ammo.unwrap_or(0);
+            let text = match active_weapon.reloading {
+                true => "Reloading...".to_string(),
+                false => format!("{ammo}/{capacity}"),
+            };
+            Some((ammo as f32 / capacity.max(1) as f32, text))
+        }
+        WeaponSupply::Heat { cooling, .. } => {
+            let heat = (active_weapon.heat - cooling * time.delta_seconds()).max(0.0);
+            active_weapon.heat = heat;
+            Some((heat, format!("Heat {:.0}%", heat * 100.0)))
+        }
+    };
+
+    egui::Area::new("weapon")
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-16.0, -16.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.strong(&def.name);
+
+            if let Some((fraction, text)) = status {
+                ui.add(
+                    egui::ProgressBar::new(fraction)
+                        .desired_width(160.0)
+                        .text(text),
+                );
+            }
+        });
+}
+
+fn ability_ui(
+    mut contexts: EguiContexts,
+    mut state: ResMut<AbilityState>,
+    ability_defs: Res<AbilityDefs>,
+    ship: Res<ShipDef>,
+    time: Res<Time>,
+) {
+    if state.cooldowns.is_empty() {
+        return;
+    }
+
+    let delta = time.delta_seconds();
+    state.energy = (state.energy + ship.energy_regen * delta).min(ship.energy);
+    for time_left in state.cooldowns.iter_mut().chain(state.active.iter_mut()) {
+        *time_left = (*time_left - delta).max(0.0);
+    }
+
+    egui::Area::new("abilities")
+        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -16.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.add(
+                egui::ProgressBar::new(state.energy / ship.energy.max(1.0))
+                    .desired_width(240.0)
+                    .text(format!("Energy {:.0}", state.energy)),
+            );
+
+            ui.horizontal(|ui| {
+                for (index, def) in ability_defs.abilities.iter().enumerate() {
+                    let cooldown = state.cooldowns.get(index).copied().unwrap_or(0.0);
+                    let active = state.active.get(index).copied().unwrap_or(0.0);
+                    let key = ABILITY_KEYS
+                        .get(index)
+                        .map_or(String::new(), |key| format!("[{key:?}] "));
+
+                    let (fraction, text) = if active > 0.0 {
+                        (
+                            active / def.duration.max(f32::EPSILON),
+                            format!("{active:.1}s"),
+                        )
+                    } else if cooldown > 0.0 {
+                        let total = def.duration + def.cooldown;
+                        (
+                            1.0 - cooldown / total.max(f32::EPSILON),
+                            format!("{cooldown:.1}s"),
+                        )
+                    } else if state.energy < def.cost {
+                        (1.0, "No energy".to_string())
+                    } else {
+                        (1.0, "Ready".to_string())
+                    };
+
+                    ui.vertical(|ui| {
+                        ui.label(format!("{key}{}", def.name));
+                        ui.add(
+                            egui::ProgressBar::new(fraction)
+                                .desired_width(76.0)
+                                .text(text),
+                        );
+                    });
+                }
+            });
+        });
+}
+
+fn scoreboard_ui(
+    mut contexts: EguiContexts,
+    keyboard_input: Res<Input<KeyCode>>,
+    lobby: Res<ClientLobby>,
+) {
+    if !keyboard_input.pressed(KeyCode::Tab) {
+        return;
+    }
+
+    let mut players: Vec<_> = lobby.players.values().collect();
+    players.sort_by_key(|player| (player.team, -player.score.points()));
+
+    egui::Window::new("Scoreboard")
+        .collapsible(false)
+        .resizable(false)
+        .title_bar(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Grid::new("scores")
+                .striped(true)
+                .min_col_width(48.0)
+                .show(ui, |ui| {
+                    for header in ["Name", "Team", "Score", "K", "D", "A", "K/D", "Ping"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+
+                    for player in players {
+                        let score = &player.score;
+
+                        ui.label(&player.name);
+                        ui.label(player.team.map_or("-".to_string(), |team| team.to_string()));
+                        ui.label(score.points().to_string());
+                        ui.label(score.kills.to_string());
+                        ui.label(score.deaths.to_string());
+                        ui.label(score.assists.to_string());
+                        ui.label(format!("{:.2}", score.kill_death_ratio()));
+                        ui.label(format!("{:.0} ms", player.ping));
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+fn match_ui(mut contexts: EguiContexts, mut match_status: ResMut<MatchStatus>, time: Res<Time>) {
+    if let Some(time_left) = &mut match_status.time_left {
+        *time_left = (*time_left - time.delta_seconds()).max(0.0);
+    }
+
+    let time_left = match_status.time_left.unwrap_or_default().ceil() as u32;
+    let clock = format!("{}:{:02}", time_left / 60, time_left % 60);
+
+    let text = match match_status.phase {
+        MatchPhase::Warmup => format!("Warmup {clock}"),
+        MatchPhase::Countdown => format!("Match starts in {time_left}"),
+        MatchPhase::InProgress => clock,
+        MatchPhase::PostMatch | MatchPhase::MapChange => "Match over".to_string(),
+    };
+
+    egui::Area::new("match")
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 16.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading(text);
+        });
+
+    if match_status.phase != MatchPhase::PostMatch {
+        return;
+    }
+
+    let winner = match &match_status.winner {
+        Some(MatchWinner::Player { name, .. }) => format!("{name} wins!"),
+        Some(MatchWinner::Team(team)) => format!("{team} wins!"),
+        Some(MatchWinner::Survival {
+            waves,
+            survived: true,
+        }) => format!("Survived {waves} waves!"),
+        Some(MatchWinner::Survival {
+            waves,
+            survived: false,
+        }) => format!("Overrun after {waves} waves"),
+        Some(MatchWinner::Draw) | None => "Draw!".to_string(),
+    };
+
+    egui::Window::new("Match over")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, -100.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading(winner);
+        });
+}
+
+fn survival_ui(
+    mut contexts: EguiContexts,
+    mut survival_status: ResMut<SurvivalStatus>,
+    match_status: Res<MatchStatus>,
+    time: Res<Time>,
+) {
+    let Some(status) = &mut survival_status.0 else {
+        return;
+    };
+    if match_status.phase != MatchPhase::InProgress {
+        return;
+    }
+
+    let text = match &mut status.next_wave {
+        Some(next_wave) => {
+            *next_wave = (*next_wave - time.delta_seconds()).max(0.0);
+            format!("Wave {} in {:.0}", status.wave + 1, next_wave.ceil())
+        }
+        None => format!(
+            "Wave {} · {} enemies left",
+            status.wave, status.enemies_remaining
+        ),
+    };
+
+    egui::Area::new("survival")
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 48.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                ui.label(text);
+
+                let mut score = format!("Score {}", status.score);
+                if let Some(lives) = status.lives {
+                    score.push_str(&format!(" · Lives {lives}"));
+                }
+                ui.label(score);
+            });
+        });
+}
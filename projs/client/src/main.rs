@@ -3,25 +3,46 @@ use std::path::Path;
 use bevy::{
     core_pipeline::{bloom::BloomSettings, tonemapping::Tonemapping},
     input::common_conditions::input_toggle_active,
+    log::{Level, LogPlugin},
+    math::vec2,
     prelude::*,
 };
 use bevy_ecs_ldtk::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
-use blitz_common::{panic_on_error_system, PlayerCommand};
+use blitz_common::{
+    AbilityDef, AbilityDefs, CollisionGrid, EnemyDefs, PlayerCommand, ShipDef, WeaponDefs,
+    ABILITIES_FILE, ENEMIES_FILE, SHIP_FILE, WEAPONS_FILE, WORLD_FILE,
+};
+use debug::ClientDebugPlugin;
+use enemies::ClientEnemiesPlugin;
 use exit::exit_system;
+use hud::ClientHudPlugin;
+use map::ClientMapPlugin;
+use menu::ClientMenuPlugin;
 
 use networking::{resources::ControlledPlayer, ClientNetworkPlugin};
+use pickups::ClientPickupsPlugin;
 use player::ClientPlayerPlugin;
 use resources::{
-    AudioAtlas, Explosion, ExplosionTimer, ExplosionToSpawn, Textures, ASSETS_DIR,
-    PLAYER_LASER_SPRITE, PLAYER_SPRITE,
+    AudioAtlas, Colliders, Explosion, ExplosionRadius, ExplosionTimer, ExplosionToSpawn, Textures,
+    ASSETS_DIR, EXPLOSION_FRAME_SIZE, PLAYER_LASER_SPRITE, PLAYER_SPRITE, PLAYER_TEAM_A_SPRITE,
 };
+use status::ClientStatusPlugin;
+use zones::ClientZonesPlugin;
 
+mod debug;
+mod enemies;
 mod exit;
+mod hud;
+mod map;
+mod menu;
 mod networking;
+mod pickups;
 mod player;
 mod resources;
+mod status;
+mod zones;
 
 fn main() {
     let mut app = App::new();
@@ -34,15 +55,27 @@ fn main() {
                 }),
                 ..Default::default()
             })
-            .set(ImagePlugin::default_nearest()),
+            .set(ImagePlugin::default_nearest())
+            // Overridden by `RUST_LOG` when set
+            .set(LogPlugin {
+                filter: "wgpu=error,naga=warn,client=info".to_string(),
+                level: Level::INFO,
+            }),
     );
 
+    app.add_plugin(ClientMenuPlugin);
+    app.add_plugin(ClientHudPlugin);
     app.add_plugin(ClientPlayerPlugin);
     app.add_plugin(ClientNetworkPlugin);
     app.add_plugin(WorldInspectorPlugin::default().run_if(input_toggle_active(true, KeyCode::I)));
 
-    app.add_plugin(LdtkPlugin)
-        .insert_resource(LevelSelection::Index(0));
+    app.add_plugin(LdtkPlugin);
+    app.add_plugin(ClientMapPlugin);
+    app.add_plugin(ClientPickupsPlugin);
+    app.add_plugin(ClientEnemiesPlugin);
+    app.add_plugin(ClientStatusPlugin);
+    app.add_plugin(ClientZonesPlugin);
+    app.add_plugin(ClientDebugPlugin);
 
     app.add_startup_system(setup);
 
@@ -50,7 +83,6 @@ fn main() {
     app.add_system(explosion_to_spawn);
     app.add_system(animate_explosion);
 
-    app.add_system(panic_on_error_system);
     app.add_system(exit_system);
 
     app.run();
@@ -76,25 +108,88 @@ fn setup(
 
     let asset_path = Path::new(ASSETS_DIR);
 
+    // Weapons, the server validates every shot so these only drive sprites, sounds and the HUD
+    let weapon_defs = WeaponDefs::load(&asset_path.join(WEAPONS_FILE)).unwrap_or_else(|e| {
+        error!("Failed to load weapons, falling back to the default blaster: {e}");
+        WeaponDefs::default()
+    });
+    let enemy_defs = EnemyDefs::load(&asset_path.join(ENEMIES_FILE)).unwrap_or_else(|e| {
+        error!("Failed to load enemies, they will use the player sprite: {e}");
+        EnemyDefs::default()
+    });
+    let ship = ShipDef::load(&asset_path.join(SHIP_FILE)).unwrap_or_else(|e| {
+        error!("Failed to load the ship, falling back to the default collider: {e}");
+        ShipDef::default()
+    });
+    let ability_defs = AbilityDefs::load(&asset_path.join(ABILITIES_FILE)).unwrap_or_else(|e| {
+        error!("Failed to load abilities, the HUD won't show any: {e}");
+        AbilityDefs::default()
+    });
+    // Enemy and ability weapons are looked up by id like everyone else's
+    let weapons: Vec<_> = weapon_defs
+        .weapons
+        .iter()
+        .chain(enemy_defs.enemies.iter().map(|enemy| &enemy.weapon))
+        .chain(ability_defs.abilities.iter().filter_map(AbilityDef::weapon))
+        .collect();
+
     // Textures
     let texture_handle = asset_server.load(asset_path.join("explo_a_sheet.png"));
-    let texture_atlas =
-        TextureAtlas::from_grid(texture_handle, Vec2 { x: 64.0, y: 64.0 }, 4, 4, None, None);
+    let frame_size = Vec2::splat(EXPLOSION_FRAME_SIZE);
+    let texture_atlas = TextureAtlas::from_grid(texture_handle, frame_size, 4, 4, None, None);
 
     commands.insert_resource(Textures {
         player: asset_server.load(asset_path.join(PLAYER_SPRITE)),
+        player_team_a: asset_server.load(asset_path.join(PLAYER_TEAM_A_SPRITE)),
         player_laser: asset_server.load(asset_path.join(PLAYER_LASER_SPRITE)),
+        weapons: weapons
+            .iter()
+            .map(|weapon| {
+                let sprite = asset_server.load(asset_path.join(&weapon.sprite));
+                (weapon.id.clone(), sprite)
+            })
+            .collect(),
+        enemies: enemy_defs
+            .enemies
+            .iter()
+            .map(|enemy| {
+                let sprite = asset_server.load(asset_path.join(&enemy.sprite));
+                (enemy.id.clone(), sprite)
+            })
+            .collect(),
         explosion: texture_atlases.add(texture_atlas),
     });
 
     // Sound
     commands.insert_resource(AudioAtlas {
         player_laser: asset_server.load(asset_path.join("player_laser.ogg")),
+        weapons: weapons
+            .iter()
+            .map(|weapon| {
+                let sound = asset_server.load(asset_path.join(&weapon.sound));
+                (weapon.id.clone(), sound)
+            })
+            .collect(),
     });
+    commands.insert_resource(Colliders {
+        ship: ship.collider,
+        weapons: weapons
+            .iter()
+            .map(|weapon| (weapon.id.clone(), weapon.collider))
+            .collect(),
+        enemies: enemy_defs
+            .enemies
+            .iter()
+            .map(|enemy| (enemy.id.clone(), enemy.collider))
+            .collect(),
+    });
+    commands.insert_resource(weapon_defs);
+    commands.insert_resource(ability_defs);
+    commands.insert_resource(ship);
 
     // World
     commands.spawn(LdtkWorldBundle {
-        ldtk_handle: asset_server.load(asset_path.join("world.ldtk")),
+        ldtk_handle: asset_server.load(asset_path.join(WORLD_FILE)),
         ..Default::default()
     });
 }
@@ -102,6 +197,8 @@ fn setup(
 fn move_camera(
     mut query_camera: Query<&mut Transform, With<Camera>>,
     query_player: Query<&Transform, (With<ControlledPlayer>, Without<Camera>)>,
+    windows: Query<&Window>,
+    collision: Res<CollisionGrid>,
     time: Res<Time>,
 ) {
     if let Some(player_transform) = query_player.iter().next() {
@@ -116,21 +213,37 @@ fn move_camera(
             );
 
             camera_transform.translation.z = old_z;
+
+            // Keep the view inside the level when it is larger than the window
+            if let Ok(window) = windows.get_single() {
+                let half_view = vec2(window.width(), window.height()) / 2.0;
+                let level_size = collision.size();
+
+                for axis in 0..2 {
+                    if level_size[axis] > half_view[axis] * 2.0 {
+                        camera_transform.translation[axis] = camera_transform.translation[axis]
+                            .clamp(half_view[axis], level_size[axis] - half_view[axis]);
+                    }
+                }
+            }
         }
     }
 }
 
 fn explosion_to_spawn(
     mut commands: Commands,
-    query: Query<(Entity, &ExplosionToSpawn)>,
+    query: Query<(Entity, &ExplosionToSpawn, Option<&ExplosionRadius>)>,
     textures: Res<Textures>,
 ) {
-    for (explosion_spawn_entity, explosion_to_spawn) in query.iter() {
+    for (explosion_spawn_entity, explosion_to_spawn, radius) in query.iter() {
+        let scale = radius.map_or(1.0, |radius| radius.0 * 2.0 / EXPLOSION_FRAME_SIZE);
+
         commands
             .spawn(SpriteSheetBundle {
                 texture_atlas: textures.explosion.clone(),
                 transform: Transform {
                     translation: explosion_to_spawn.0,
+                    scale: Vec3::splat(scale),
                     ..Default::default()
                 },
                 ..Default::default()
@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_inspector_egui::bevy_egui::{egui, EguiContexts};
+use blitz_common::{CollisionGrid, MapData, PlayerInput, ServerMessage, WORLD_FILE};
+
+use crate::{
+    menu::{ClientState, DisconnectReason},
+    resources::ASSETS_DIR,
+};
+
+/// Level the server sent us to that bevy_ecs_ldtk hasn't finished spawning yet.
+/// Input is held back while it is set.
+#[derive(Debug, Default, Resource)]
+pub struct LevelLoading(Option<String>);
+
+pub fn level_ready(loading: Res<LevelLoading>) -> bool {
+    loading.0.is_none()
+}
+
+pub struct ClientMapPlugin;
+impl Plugin for ClientMapPlugin {
+    fn build(&self, app: &mut App) {
+        // bevy_ecs_ldtk renders the level, this parsed copy is what the server collides with
+        let map = MapData::load(&Path::new(ASSETS_DIR).join(WORLD_FILE)).unwrap_or_else(|e| {
+            error!("Failed to parse map: {e}");
+            MapData::default()
+        });
+
+        let collision = map
+            .levels
+            .first()
+            .map(|level| level.collision.clone())
+            .unwrap_or_default();
+        let selection = map
+            .levels
+            .first()
+            .map_or(LevelSelection::Index(0), |level| {
+                LevelSelection::Identifier(level.identifier.clone())
+            });
+
+        app.insert_resource(map);
+        app.insert_resource(collision);
+        app.insert_resource(selection);
+        app.init_resource::<LevelLoading>();
+
+        app.add_systems((load_level, finish_loading, loading_ui).chain());
+        app.add_system(reset_loading.in_schedule(OnExit(ClientState::InGame)));
+    }
+}
+
+fn load_level(
+    mut server_messages: EventReader<ServerMessage>,
+    mut loading: ResMut<LevelLoading>,
+    mut selection: ResMut<LevelSelection>,
+    mut collision: ResMut<CollisionGrid>,
+    mut player_input: ResMut<PlayerInput>,
+    mut disconnect_reason: ResMut<DisconnectReason>,
+    mut next_state: ResMut<NextState<ClientState>>,
+    map: Res<MapData>,
+) {
+    for message in server_messages.iter() {
+        let ServerMessage::LoadLevel { identifier, hash } = message else {
+            continue;
+        };
+
+        let Some(level) = map.level(identifier).filter(|level| level.hash == *hash) else {
+            let found = map.level(identifier).is_some();
+            error!(level = %identifier, found, "Level differs from the server's");
+
+            disconnect_reason.0 = Some(format!(
+                "Your copy of level {identifier} doesn't match the server's, please update the game"
+            ));
+            next_state.set(ClientState::Menu);
+            return;
+        };
+
+        info!(level = %identifier, "Loading level");
+        *collision = level.collision.clone();
+        // Stop the ship where it is until the level shows up
+        *player_input = PlayerInput::default();
+
+        let target = LevelSelection::Identifier(identifier.clone());
+        if *selection != target {
+            *selection = target;
+            loading.0 = Some(level.iid.clone());
+        }
+    }
+}
+
+fn finish_loading(mut level_events: EventReader<LevelEvent>, mut loading: ResMut<LevelLoading>) {
+    for event in level_events.iter() {
+        if let LevelEvent::Transformed(iid) = event {
+            if loading.0.as_ref() == Some(iid) {
+                info!("Level ready");
+                loading.0 = None;
+            }
+        }
+    }
+}
+
+fn loading_ui(mut contexts: EguiContexts, loading: Res<LevelLoading>) {
+    if loading.0.is_none() {
+        return;
+    }
+
+    egui::Area::new("loading")
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading("Loading level...");
+        });
+}
+
+fn reset_loading(mut loading: ResMut<LevelLoading>) {
+    loading.0 = None;
+}
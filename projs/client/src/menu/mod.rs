@@ -0,0 +1,74 @@
+use bevy::{app::AppExit, prelude::*};
+use bevy_inspector_egui::bevy_egui::{egui, EguiContexts, EguiPlugin};
+
+use crate::networking::{new_renet_client, resources::QueueStatus};
+
+#[derive(States, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum ClientState {
+    Menu,
+    #[default]
+    InGame,
+}
+
+/// Why we were last dropped from a server, shown in the menu.
+#[derive(Debug, Default, Resource)]
+pub struct DisconnectReason(pub Option<String>);
+
+pub struct ClientMenuPlugin;
+impl Plugin for ClientMenuPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        app.add_state::<ClientState>();
+        app.init_resource::<DisconnectReason>();
+
+        app.add_system(menu_ui.in_set(OnUpdate(ClientState::Menu)));
+        app.add_system(queue_ui.in_set(OnUpdate(ClientState::InGame)));
+    }
+}
+
+fn menu_ui(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut reason: ResMut<DisconnectReason>,
+    mut next_state: ResMut<NextState<ClientState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    egui::Window::new("Blitz")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            if let Some(reason) = &reason.0 {
+                ui.label(reason);
+                ui.separator();
+            }
+
+            if ui.button("Connect").clicked() {
+                // A reason left from the last connection would hide why this one ends
+                reason.0 = None;
+                commands.insert_resource(new_renet_client());
+                next_state.set(ClientState::InGame);
+            }
+
+            if ui.button("Quit").clicked() {
+                exit.send(AppExit);
+            }
+        });
+}
+
+fn queue_ui(mut contexts: EguiContexts, queue_status: Res<QueueStatus>) {
+    let Some((position, length)) = queue_status.0 else {
+        return;
+    };
+
+    egui::Window::new("Server full")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 16.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!("Waiting for a free slot: {position} of {length} in queue"));
+        });
+}
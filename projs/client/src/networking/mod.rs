@@ -1,23 +1,28 @@
 use bevy::{math::vec3, prelude::*};
 use bevy_renet::{
-    renet::{ClientAuthentication, RenetClient, RenetConnectionConfig},
+    renet::{ClientAuthentication, RenetClient, RenetConnectionConfig, RenetError},
     RenetClientPlugin,
 };
 use blitz_common::{
-    ClientChannel, NetworkedEntities, PlayerInput, ServerChannel, ServerMessage, PROTOCOL_ID,
+    ClientChannel, ConnectionData, MatchPhase, NetworkedEntities, PlayerInput, Score,
+    ServerChannel, ServerMessage, Team, PROTOCOL_ID,
 };
 
-use std::{net::UdpSocket, time::SystemTime};
+use std::{env, net::UdpSocket, time::SystemTime};
 
 use crate::{
     exit::exit_system,
+    menu::{ClientState, DisconnectReason},
     networking::resources::ControlledPlayer,
-    resources::{AudioAtlas, ExplosionToSpawn, Textures},
+    resources::{AudioAtlas, Colliders, ExplosionRadius, ExplosionToSpawn, Textures},
     PlayerCommand,
 };
 
 pub mod resources;
-use resources::{ClientLobby, NetworkMapping, PlayerInfo};
+use resources::{
+    AbilityState, ActiveWeapon, ClientLobby, MatchStatus, NetworkMapping, PlayerInfo, QueueStatus,
+    SurvivalStatus, SurvivalWave,
+};
 
 pub fn client_connection_config() -> RenetConnectionConfig {
     RenetConnectionConfig {
@@ -35,11 +40,17 @@ pub fn new_renet_client() -> RenetClient {
         .duration_since(SystemTime::UNIX_EPOCH)
         .unwrap();
     let client_id = current_time.as_millis() as u64;
+    let name = env::var("BLITZ_PLAYER_NAME").unwrap_or_else(|_| format!("Player {client_id}"));
+    let spectator = env::var("BLITZ_SPECTATE").is_ok();
+    let mut connection_data = ConnectionData::new(&name, spectator);
+    if let Ok(token) = env::var("BLITZ_ADMIN_TOKEN") {
+        connection_data = connection_data.with_admin_token(&token);
+    }
     let authentication = ClientAuthentication::Unsecure {
         client_id,
         protocol_id: PROTOCOL_ID,
         server_addr,
-        user_data: None,
+        user_data: Some(connection_data.to_user_data()),
     };
     RenetClient::new(current_time, socket, connection_config, authentication).unwrap()
 }
@@ -53,18 +64,91 @@ impl Plugin for ClientNetworkPlugin {
         app.insert_resource(NetworkMapping::default());
 
         app.init_resource::<ClientLobby>();
+        app.init_resource::<QueueStatus>();
+        app.init_resource::<MatchStatus>();
+        app.init_resource::<SurvivalStatus>();
+        app.init_resource::<ActiveWeapon>();
+        app.init_resource::<AbilityState>();
 
         app.add_event::<PlayerCommand>();
+        app.add_event::<ServerMessage>();
 
         app.add_systems(
             (
-                client_sync_players.run_if(bevy_renet::client_connected),
-                client_send_input.run_if(bevy_renet::client_connected),
-                client_send_player_commands.run_if(bevy_renet::client_connected),
+                client_sync_players.run_if(in_game()),
+                client_send_input.run_if(in_game()),
+                client_send_player_commands.run_if(in_game()),
             )
                 .after(exit_system),
         );
+
+        app.add_system(
+            client_connection_lost
+                .in_set(OnUpdate(ClientState::InGame))
+                .run_if(resource_exists::<RenetClient>()),
+        );
+        app.add_system(leave_game.in_schedule(OnExit(ClientState::InGame)));
+    }
+}
+
+/// Run condition for systems that need a live connection to the server.
+pub fn in_game() -> impl Condition<()> {
+    in_state(ClientState::InGame).and_then(bevy_renet::client_connected)
+}
+
+fn client_connection_lost(
+    client: Res<RenetClient>,
+    mut renet_errors: EventReader<RenetError>,
+    mut disconnect_reason: ResMut<DisconnectReason>,
+    mut next_state: ResMut<NextState<ClientState>>,
+) {
+    for e in renet_errors.iter() {
+        let error = match e {
+            RenetError::Netcode(e) => e.to_string(),
+            RenetError::Rechannel(e) => e.to_string(),
+            RenetError::IO(e) => e.to_string(),
+        };
+        error!(%error, "Connection error");
+
+        disconnect_reason
+            .0
+            .get_or_insert_with(|| format!("Connection lost: {error}"));
+        next_state.set(ClientState::Menu);
+    }
+
+    if let Some(reason) = client.disconnected() {
+        disconnect_reason
+            .0
+            .get_or_insert_with(|| format!("Disconnected: {reason:?}"));
+        next_state.set(ClientState::Menu);
+    }
+}
+
+/// Drops the connection and everything that was replicated from the server.
+fn leave_game(
+    mut commands: Commands,
+    client: Option<ResMut<RenetClient>>,
+    mut lobby: ResMut<ClientLobby>,
+    mut network_mapping: ResMut<NetworkMapping>,
+    mut queue_status: ResMut<QueueStatus>,
+    mut active_weapon: ResMut<ActiveWeapon>,
+    mut ability_state: ResMut<AbilityState>,
+    mut survival_status: ResMut<SurvivalStatus>,
+) {
+    queue_status.0 = None;
+    survival_status.0 = None;
+    *active_weapon = ActiveWeapon::default();
+    *ability_state = AbilityState::default();
+
+    if let Some(mut client) = client {
+        client.disconnect();
+        commands.remove_resource::<RenetClient>();
+    }
+
+    for (_, client_entity) in network_mapping.0.drain() {
+        commands.entity(client_entity).despawn_recursive();
     }
+    lobby.players.clear();
 }
 
 pub fn client_send_input(player_input: Res<PlayerInput>, mut client: ResMut<RenetClient>) {
@@ -90,20 +174,31 @@ pub struct PlayerEntity;
 pub fn client_sync_players(
     mut commands: Commands,
     textures: Res<Textures>,
+    colliders: Res<Colliders>,
     mut lobby: ResMut<ClientLobby>,
     mut client: ResMut<RenetClient>,
     mut network_mapping: ResMut<NetworkMapping>,
     audio: Res<Audio>,
     audio_atlas: Res<AudioAtlas>,
-    player_query: Query<(Entity, &Transform), With<PlayerEntity>>,
+    mut disconnect_reason: ResMut<DisconnectReason>,
+    mut next_state: ResMut<NextState<ClientState>>,
+    mut queue_status: ResMut<QueueStatus>,
+    mut match_status: ResMut<MatchStatus>,
+    mut survival_status: ResMut<SurvivalStatus>,
+    mut active_weapon: ResMut<ActiveWeapon>,
+    mut ability_state: ResMut<AbilityState>,
+    mut forwarded_messages: EventWriter<ServerMessage>,
+    player_query: Query<&Transform, With<PlayerEntity>>,
 ) {
     let client_id = client.client_id();
+    let _client_span = info_span!("client", client_id).entered();
+
     while let Some(message) = client.receive_message(ServerChannel::ServerMessages) {
         let server_message =
             bincode::deserialize(&message).expect("Failed to Deserialize message!");
         match server_message {
-            ServerMessage::PlayerCreate { id, entity } => {
-                println!("Player {} connected.", id);
+            ServerMessage::PlayerCreate { id, name, entity } => {
+                info!(player_id = id, %name, network_id = ?entity, "Player connected");
 
                 let mut client_entity = commands.spawn(SpriteBundle {
                     texture: textures.player.clone(),
@@ -118,26 +213,32 @@ pub fn client_sync_players(
                     },
                     ..Default::default()
                 });
-                client_entity.insert(PlayerEntity);
+                client_entity.insert(PlayerEntity).insert(colliders.ship);
 
                 if client_id == id {
                     client_entity.insert(ControlledPlayer);
+                    queue_status.0 = None;
                 }
 
                 let player_info = PlayerInfo {
                     server_entity: entity,
                     client_entity: client_entity.id(),
+                    name,
+                    score: Score::default(),
+                    team: None,
+                    ping: 0.0,
                 };
 
                 lobby.players.insert(id, player_info);
                 network_mapping.0.insert(entity, client_entity.id());
             }
             ServerMessage::PlayerDisconnected { id } => {
-                println!("Player {} disconnected.", id);
+                info!(player_id = id, "Player disconnected");
 
                 if let Some(PlayerInfo {
                     client_entity,
                     server_entity,
+                    ..
                 }) = lobby.players.remove(&id)
                 {
                     commands.entity(client_entity).despawn();
@@ -148,10 +249,12 @@ pub fn client_sync_players(
                 entity,
                 transform: translation,
                 rotation,
+                weapon,
             } => {
-                println!("SpawnProjectile message! {entity:?}");
-                let projectile_entity = commands.spawn(SpriteBundle {
-                    texture: textures.player_laser.clone(),
+                debug!(network_id = ?entity, %weapon, "Spawn projectile");
+                let texture = textures.weapons.get(&weapon).unwrap_or(&textures.player_laser);
+                let mut projectile_entity = commands.spawn(SpriteBundle {
+                    texture: texture.clone(),
                     transform: Transform {
                         translation: vec3(translation.x, translation.y, 0.0),
                         rotation,
@@ -163,53 +266,172 @@ pub fn client_sync_players(
                     },
                     ..Default::default()
                 });
+                if let Some(collider) = colliders.weapons.get(&weapon) {
+                    projectile_entity.insert(*collider);
+                }
 
-                audio.play(audio_atlas.player_laser.clone());
+                let sound = audio_atlas
+                    .weapons
+                    .get(&weapon)
+                    .unwrap_or(&audio_atlas.player_laser);
+                audio.play(sound.clone());
 
                 network_mapping.0.insert(entity, projectile_entity.id());
             }
             ServerMessage::DespawnProjectile { entity } => {
-                println!("DespawnProjectile message! {entity:?}");
+                debug!(network_id = ?entity, "Despawn projectile");
                 if let Some(entity) = network_mapping.0.remove(&entity) {
                     commands.entity(entity).despawn();
                 }
             }
+            ServerMessage::Explosion { position, radius } => {
+                debug!(?position, radius, "Explosion");
+                commands.spawn((
+                    ExplosionToSpawn(position.extend(0.0)),
+                    ExplosionRadius(radius),
+                ));
+            }
             ServerMessage::DespawnPlayer { entity } => {
-                println!("Despawning Player {:?}", entity);
+                debug!(network_id = ?entity, "Despawn player");
 
                 if let Some(client_entity) = network_mapping.0.get(&entity) {
-                    for (entity, transform) in player_query.iter() {
-                        if *client_entity == entity {
-                            commands
-                                .spawn_empty()
-                                .insert(ExplosionToSpawn(transform.translation));
-                        }
+                    if let Ok(transform) = player_query.get(*client_entity) {
+                        commands
+                            .spawn_empty()
+                            .insert(ExplosionToSpawn(transform.translation));
                     }
-                }
 
-                // if let Some(entity) = network_mapping.0.remove(&entity) {
-                //     commands.entity(entity).despawn();
-                // }
+                    commands.entity(*client_entity).insert(Visibility::Hidden);
+                }
             }
             ServerMessage::RespawnPlayer { entity } => {
-                println!("Respawning Player {:?}", entity);
-
-                // let player_entity = commands
-                //     .spawn(SpriteBundle {
-                //         texture: textures.player.clone(),
-                //         transform: Transform {
-                //             translation: vec3(0.0, 0.0, 0.0),
-                //             scale: vec3(0.5, 0.5, 1.0),
-                //             ..Default::default()
-                //         },
-                //         ..Default::default()
-                //     })
-                //     .id();
-
-                // if let Some(client_entity) = network_mapping.0.get_mut(&entity) {
-                //     *client_entity = player_entity;
-                // }
+                debug!(network_id = ?entity, "Respawn player");
+
+                if let Some(client_entity) = network_mapping.0.get(&entity) {
+                    commands.entity(*client_entity).insert(Visibility::Inherited);
+                }
+            }
+            ServerMessage::PlayerScore { id, score } => {
+                if let Some(player_info) = lobby.players.get_mut(&id) {
+                    player_info.score = score;
+                }
+            }
+            ServerMessage::PlayerTeam { id, team } => {
+                if let Some(player_info) = lobby.players.get_mut(&id) {
+                    info!(player_id = id, %team, "Player joined team");
+                    player_info.team = Some(team);
+
+                    let texture = match team {
+                        Team::A => textures.player_team_a.clone(),
+                        Team::B => textures.player.clone(),
+                    };
+                    commands
+                        .entity(player_info.client_entity)
+                        .insert((team, texture));
+                }
+            }
+            ServerMessage::PlayerPings { pings } => {
+                for (id, ping) in pings {
+                    if let Some(player_info) = lobby.players.get_mut(&id) {
+                        player_info.ping = ping;
+                    }
+                }
+            }
+            ServerMessage::PlayerHealth { entity, health } => {
+                if let Some(client_entity) = network_mapping.0.get(&entity) {
+                    commands.entity(*client_entity).insert(health);
+                }
+            }
+            ServerMessage::ConnectionRejected { reason, expires } => {
+                warn!(%reason, ?expires, "Rejected by server");
+
+                disconnect_reason.0 = Some(match expires {
+                    Some(expires) => format!("{reason}\nUntil {expires} (unix time)"),
+                    None => reason,
+                });
+                next_state.set(ClientState::Menu);
+            }
+            ServerMessage::MatchState {
+                phase,
+                time_left,
+                winner,
+            } => {
+                info!(?phase, ?time_left, ?winner, "Match state");
+                // Only survival matches in progress report their waves
+                if phase != MatchPhase::InProgress {
+                    survival_status.0 = None;
+                }
+                *match_status = MatchStatus {
+                    phase,
+                    time_left,
+                    winner,
+                };
+            }
+            ServerMessage::SurvivalState {
+                wave,
+                enemies_remaining,
+                score,
+                lives,
+                next_wave,
+            } => {
+                survival_status.0 = Some(SurvivalWave {
+                    wave,
+                    enemies_remaining,
+                    score,
+                    lives,
+                    next_wave,
+                });
+            }
+            ServerMessage::WeaponStatus {
+                weapon,
+                ammo,
+                heat,
+                reloading,
+            } => {
+                *active_weapon = ActiveWeapon {
+                    weapon: Some(weapon),
+                    ammo,
+                    heat,
+                    reloading,
+                };
+            }
+            ServerMessage::AbilityStatus {
+                energy,
+                cooldowns,
+                active,
+            } => {
+                *ability_state = AbilityState {
+                    energy,
+                    cooldowns,
+                    active,
+                };
+            }
+            ServerMessage::JoinQueued { position, length } => {
+                info!(position, length, "Waiting in join queue");
+                queue_status.0 = Some((position, length));
+            }
+            ServerMessage::ServerShutdown {
+                reason,
+                reconnect_after,
+            } => {
+                info!(%reason, ?reconnect_after, "Server shutting down");
+
+                disconnect_reason.0 = Some(match reconnect_after {
+                    Some(secs) => format!("{reason}\nThe server will be back in {secs:.0} seconds"),
+                    None => reason,
+                });
+                next_state.set(ClientState::Menu);
             }
+            // Handled by the plugins owning these features
+            forwarded @ (ServerMessage::LoadLevel { .. }
+            | ServerMessage::PickupSpawned { .. }
+            | ServerMessage::PickupCollected { .. }
+            | ServerMessage::DespawnPickup { .. }
+            | ServerMessage::PlayerStatus { .. }
+            | ServerMessage::SpawnEnemy { .. }
+            | ServerMessage::DespawnEnemy { .. }
+            | ServerMessage::ZoneTriggered { .. }
+            | ServerMessage::DebugPath { .. }) => forwarded_messages.send(forwarded),
         }
     }
 
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use bevy::prelude::{Component, Entity, Resource};
+use blitz_common::{MatchPhase, MatchWinner, Score, Team};
 
 #[derive(Default, Resource)]
 pub struct NetworkMapping(pub HashMap<Entity, Entity>);
@@ -9,6 +10,11 @@ pub struct NetworkMapping(pub HashMap<Entity, Entity>);
 pub struct PlayerInfo {
     pub client_entity: Entity,
     pub server_entity: Entity,
+    pub name: String,
+    pub score: Score,
+    pub team: Option<Team>,
+    /// Round trip time in milliseconds
+    pub ping: f32,
 }
 
 #[derive(Debug, Default, Resource)]
@@ -18,3 +24,48 @@ pub struct ClientLobby {
 
 #[derive(Component)]
 pub struct ControlledPlayer;
+
+/// Position and length of the join queue while waiting for a player slot.
+#[derive(Debug, Default, Resource)]
+pub struct QueueStatus(pub Option<(usize, usize)>);
+
+/// Active weapon of the controlled player as last reported by the server.
+/// `heat` is cooled down locally between updates.
+#[derive(Debug, Default, Resource)]
+pub struct ActiveWeapon {
+    pub weapon: Option<String>,
+    pub ammo: Option<u32>,
+    pub heat: f32,
+    pub reloading: bool,
+}
+
+/// Abilities of the controlled player as last reported by the server, in the order of the
+/// definitions. Energy and cooldowns are counted locally between updates.
+#[derive(Debug, Default, Resource)]
+pub struct AbilityState {
+    pub energy: f32,
+    pub cooldowns: Vec<f32>,
+    pub active: Vec<f32>,
+}
+
+/// Match phase as last reported by the server, `time_left` counted down locally.
+#[derive(Debug, Default, Resource)]
+pub struct MatchStatus {
+    pub phase: MatchPhase,
+    pub time_left: Option<f32>,
+    pub winner: Option<MatchWinner>,
+}
+
+/// Progress of a survival match as last reported by the server, `next_wave` counted down
+/// locally. Empty outside of survival matches in progress.
+#[derive(Debug, Default, Resource)]
+pub struct SurvivalStatus(pub Option<SurvivalWave>);
+
+#[derive(Debug)]
+pub struct SurvivalWave {
+    pub wave: u32,
+    pub enemies_remaining: u32,
+    pub score: u32,
+    pub lives: Option<u32>,
+    pub next_wave: Option<f32>,
+}
@@ -0,0 +1,207 @@
+use std::{collections::HashMap, path::Path};
+
+use bevy::prelude::*;
+use blitz_common::{PickupDefs, ServerMessage, PICKUPS_FILE};
+
+use crate::{
+    networking::{in_game, resources::NetworkMapping},
+    resources::ASSETS_DIR,
+};
+
+/// Seconds the pop in and out animations take.
+const PICKUP_ANIMATION_TIME: f32 = 0.3;
+/// Radians per second an idle pickup spins.
+const PICKUP_SPIN_SPEED: f32 = 1.5;
+
+#[derive(Resource)]
+struct PickupAssets {
+    sprites: HashMap<String, Handle<Image>>,
+    sounds: HashMap<String, Handle<AudioSource>>,
+}
+
+/// Holds the id of the pickup definition.
+#[derive(Component)]
+struct PickupEntity(String);
+
+#[derive(Component)]
+enum PickupAnimation {
+    /// Grows in when the pickup (re)spawns
+    Appear(Timer),
+    /// Swells and fades out when collected, hidden afterwards
+    Vanish(Timer),
+}
+
+impl PickupAnimation {
+    fn appear() -> Self {
+        Self::Appear(Timer::from_seconds(PICKUP_ANIMATION_TIME, TimerMode::Once))
+    }
+
+    fn vanish() -> Self {
+        Self::Vanish(Timer::from_seconds(PICKUP_ANIMATION_TIME, TimerMode::Once))
+    }
+}
+
+pub struct ClientPickupsPlugin;
+impl Plugin for ClientPickupsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(load_pickups);
+
+        app.add_system(sync_pickups.run_if(in_game()));
+        app.add_system(animate_pickups);
+    }
+}
+
+fn load_pickups(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let asset_path = Path::new(ASSETS_DIR);
+
+    // Only used for looks, the server decides what a pickup does
+    let defs = PickupDefs::load(&asset_path.join(PICKUPS_FILE)).unwrap_or_else(|e| {
+        error!("Failed to load pickups, they will be invisible: {e}");
+        PickupDefs::default()
+    });
+
+    commands.insert_resource(PickupAssets {
+        sprites: defs
+            .pickups
+            .iter()
+            .map(|pickup| {
+                let sprite = asset_server.load(asset_path.join(&pickup.sprite));
+                (pickup.id.clone(), sprite)
+            })
+            .collect(),
+        sounds: defs
+            .pickups
+            .iter()
+            .map(|pickup| {
+                let sound = asset_server.load(asset_path.join(&pickup.sound));
+                (pickup.id.clone(), sound)
+            })
+            .collect(),
+    });
+    commands.insert_resource(defs);
+}
+
+fn sync_pickups(
+    mut commands: Commands,
+    mut server_messages: EventReader<ServerMessage>,
+    mut network_mapping: ResMut<NetworkMapping>,
+    pickups: Query<&PickupEntity>,
+    defs: Res<PickupDefs>,
+    assets: Res<PickupAssets>,
+    audio: Res<Audio>,
+) {
+    for message in server_messages.iter() {
+        match message {
+            ServerMessage::PickupSpawned {
+                entity,
+                pickup,
+                position,
+            } => {
+                debug!(network_id = ?entity, %pickup, "Spawn pickup");
+
+                if let Some(client_entity) = network_mapping.0.get(entity) {
+                    commands
+                        .entity(*client_entity)
+                        .insert((Visibility::Inherited, PickupAnimation::appear()));
+                    continue;
+                }
+
+                let Some(def) = defs.get(pickup) else {
+                    warn!(%pickup, "Unknown pickup");
+                    continue;
+                };
+
+                let [r, g, b] = def.color;
+                let client_entity = commands
+                    .spawn(SpriteBundle {
+                        texture: assets.sprites[&def.id].clone(),
+                        transform: Transform::from_translation(position.extend(0.0)),
+                        sprite: Sprite {
+                            color: Color::rgb(r, g, b),
+                            custom_size: Some(Vec2::splat(def.size)),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .insert(PickupEntity(def.id.clone()))
+                    .insert(PickupAnimation::appear())
+                    .insert(def.collider)
+                    .id();
+
+                network_mapping.0.insert(*entity, client_entity);
+            }
+            ServerMessage::PickupCollected { entity, player } => {
+                debug!(network_id = ?entity, ?player, "Pickup collected");
+
+                let Some(client_entity) = network_mapping.0.get(entity) else {
+                    continue;
+                };
+
+                commands
+                    .entity(*client_entity)
+                    .insert(PickupAnimation::vanish());
+
+                if let Some(sound) = pickups
+                    .get(*client_entity)
+                    .ok()
+                    .and_then(|pickup| assets.sounds.get(&pickup.0))
+                {
+                    audio.play(sound.clone());
+                }
+            }
+            ServerMessage::DespawnPickup { entity } => {
+                debug!(network_id = ?entity, "Despawn pickup");
+
+                if let Some(client_entity) = network_mapping.0.remove(entity) {
+                    commands.entity(client_entity).despawn();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn animate_pickups(
+    mut commands: Commands,
+    mut pickups: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Sprite,
+            Option<&mut PickupAnimation>,
+        ),
+        With<PickupEntity>,
+    >,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, mut sprite, animation) in pickups.iter_mut() {
+        transform.rotate_z(PICKUP_SPIN_SPEED * time.delta_seconds());
+
+        let Some(mut animation) = animation else {
+            continue;
+        };
+
+        let finished = match animation.as_mut() {
+            PickupAnimation::Appear(timer) => {
+                timer.tick(time.delta());
+                transform.scale = Vec3::splat(timer.percent());
+                sprite.color.set_a(1.0);
+                timer.finished()
+            }
+            PickupAnimation::Vanish(timer) => {
+                timer.tick(time.delta());
+                transform.scale = Vec3::splat(1.0 + timer.percent());
+                sprite.color.set_a(timer.percent_left());
+
+                if timer.finished() {
+                    commands.entity(entity).insert(Visibility::Hidden);
+                }
+                timer.finished()
+            }
+        };
+
+        if finished {
+            commands.entity(entity).remove::<PickupAnimation>();
+        }
+    }
+}
@@ -1,7 +1,32 @@
-use bevy::{math::vec2, prelude::*};
-use blitz_common::{PlayerCommand, PlayerInput};
+use bevy::{input::mouse::MouseWheel, math::vec2, prelude::*};
+use blitz_common::{PlayerCommand, PlayerInput, StatusDefs, StatusModifier, Team, WeaponDefs};
 
-use crate::exit::exit_system;
+use crate::{
+    exit::exit_system,
+    map::level_ready,
+    networking::{
+        in_game,
+        resources::{ActiveWeapon, ControlledPlayer},
+    },
+    status::StatusEffects,
+};
+
+const WEAPON_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// Keys for the abilities in the order of their definitions.
+pub const ABILITY_KEYS: [KeyCode; 4] = [KeyCode::LShift, KeyCode::E, KeyCode::F, KeyCode::Q];
+/// The right mouse button also uses this ability, the charged shot by default.
+const ABILITY_MOUSE_SLOT: u8 = 2;
 
 pub struct ClientPlayerPlugin;
 impl Plugin for ClientPlayerPlugin {
@@ -10,7 +35,17 @@ impl Plugin for ClientPlayerPlugin {
 
         app.add_system(
             player_input
-                .run_if(bevy_renet::client_connected)
+                .run_if(in_game().and_then(level_ready))
+                .after(exit_system),
+        );
+        app.add_system(
+            weapon_input
+                .run_if(in_game().and_then(level_ready))
+                .after(exit_system),
+        );
+        app.add_system(
+            ability_input
+                .run_if(in_game().and_then(level_ready))
                 .after(exit_system),
         );
     }
@@ -21,8 +56,8 @@ fn player_input(
     mut player_input: ResMut<PlayerInput>,
     windows: Query<&Window>,
     mut player_commands: EventWriter<PlayerCommand>,
-    mouse_button_input: Res<Input<MouseButton>>,
     query_camera: Query<&Transform, With<Camera>>,
+    query_team: Query<&Team, With<ControlledPlayer>>,
 ) {
     player_input.left = keyboard_input.pressed(KeyCode::A) || keyboard_input.pressed(KeyCode::Left);
     player_input.right =
@@ -42,7 +77,89 @@ fn player_input(
         }
     }
 
-    if mouse_button_input.just_pressed(MouseButton::Left) {
+    if keyboard_input.just_pressed(KeyCode::T) {
+        if let Ok(team) = query_team.get_single() {
+            player_commands.send(PlayerCommand::ChangeTeam(team.opponent()));
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn weapon_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    active_weapon: Res<ActiveWeapon>,
+    weapon_defs: Res<WeaponDefs>,
+    status_query: Query<&StatusEffects, With<ControlledPlayer>>,
+    status_defs: Res<StatusDefs>,
+    mut cooldown: Local<f32>,
+    time: Res<Time>,
+) {
+    // Holding the button keeps firing at the weapon's rate, the server rejects anything faster
+    *cooldown = (*cooldown - time.delta_seconds()).max(0.0);
+    if mouse_button_input.pressed(MouseButton::Left) && *cooldown <= 0.0 {
         player_commands.send(PlayerCommand::BasicAttack);
+
+        // Same multiplier the server applies, so the effect isn't wasted waiting on the client
+        let fire_rate: f32 = status_query
+            .iter()
+            .flat_map(|effects| effects.0.iter())
+            .filter_map(|effect| Some((status_defs.get(&effect.id)?, effect.stacks)))
+            .map(|(def, stacks)| match def.modifier {
+                StatusModifier::FireRate { multiplier } => multiplier.powi(stacks as i32),
+                _ => 1.0,
+            })
+            .product();
+
+        *cooldown = active_weapon
+            .weapon
+            .as_deref()
+            .and_then(|weapon| weapon_defs.get(weapon))
+            .map_or(0.25, |def| def.cooldown())
+            / fire_rate.max(f32::EPSILON);
+    }
+
+    for (index, key) in WEAPON_KEYS.into_iter().enumerate() {
+        if keyboard_input.just_pressed(key) {
+            player_commands.send(PlayerCommand::SelectWeapon(index as u8));
+        }
+    }
+
+    for event in mouse_wheel.iter() {
+        if event.y > 0.0 {
+            player_commands.send(PlayerCommand::NextWeapon);
+        } else if event.y < 0.0 {
+            player_commands.send(PlayerCommand::PreviousWeapon);
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::R) {
+        player_commands.send(PlayerCommand::Reload);
+    }
+}
+
+/// Abilities start on press, charged ones fire on release. The server ignores whatever
+/// isn't ready or affordable.
+fn ability_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut player_commands: EventWriter<PlayerCommand>,
+) {
+    for (index, key) in ABILITY_KEYS.into_iter().enumerate() {
+        if keyboard_input.just_pressed(key) {
+            player_commands.send(PlayerCommand::ActivateAbility(index as u8));
+        }
+        if keyboard_input.just_released(key) {
+            player_commands.send(PlayerCommand::ReleaseAbility(index as u8));
+        }
+    }
+
+    if mouse_button_input.just_pressed(MouseButton::Right) {
+        player_commands.send(PlayerCommand::ActivateAbility(ABILITY_MOUSE_SLOT));
+    }
+    if mouse_button_input.just_released(MouseButton::Right) {
+        player_commands.send(PlayerCommand::ReleaseAbility(ABILITY_MOUSE_SLOT));
     }
 }
@@ -1,21 +1,44 @@
+use std::collections::HashMap;
+
 use bevy::{
     prelude::{AudioSource, Component, Handle, Image, Resource, Vec3},
     sprite::TextureAtlas,
     time::{Timer, TimerMode},
 };
+use blitz_common::Collider;
 
 pub static ASSETS_DIR: &str = env!("ASSETS_DIR");
 
 pub const PLAYER_SPRITE: &str = "player_b_01.png";
+pub const PLAYER_TEAM_A_SPRITE: &str = "player_a_01.png";
 pub const PLAYER_LASER_SPRITE: &str = "laser_a_01.png";
+/// Side of a frame of the explosion sprite sheet.
+pub const EXPLOSION_FRAME_SIZE: f32 = 64.0;
 
 #[derive(Resource)]
 pub struct Textures {
+    /// Also used for team B
     pub player: Handle<Image>,
+    pub player_team_a: Handle<Image>,
+    /// Used for projectiles of weapons without a sprite of their own
     pub player_laser: Handle<Image>,
+    /// Projectile sprites by weapon id
+    pub weapons: HashMap<String, Handle<Image>>,
+    /// Ship sprites by enemy id
+    pub enemies: HashMap<String, Handle<Image>>,
     pub explosion: Handle<TextureAtlas>,
 }
 
+/// Shapes the server hits things with, only drawn by the debug overlay.
+#[derive(Resource)]
+pub struct Colliders {
+    pub ship: Collider,
+    /// Projectile shapes by weapon id
+    pub weapons: HashMap<String, Collider>,
+    /// Ship shapes by enemy id
+    pub enemies: HashMap<String, Collider>,
+}
+
 #[derive(Resource)]
 pub struct WinSize {
     #[allow(unused)]
@@ -26,12 +49,17 @@ pub struct WinSize {
 #[derive(Resource)]
 pub struct AudioAtlas {
     pub player_laser: Handle<AudioSource>,
+    /// Firing sounds by weapon id
+    pub weapons: HashMap<String, Handle<AudioSource>>,
 }
 
 #[derive(Component)]
 pub struct Explosion;
 #[derive(Component)]
 pub struct ExplosionToSpawn(pub Vec3);
+/// Scales the explosion spawned next to it to cover this radius instead of its sprite size.
+#[derive(Component)]
+pub struct ExplosionRadius(pub f32);
 #[derive(Component)]
 pub struct ExplosionTimer(pub Timer);
 
@@ -0,0 +1,178 @@
+use std::{collections::HashMap, path::Path};
+
+use bevy::prelude::*;
+use blitz_common::{ServerMessage, StatusDefs, StatusStack, STATUS_EFFECTS_FILE};
+
+use crate::{
+    networking::{in_game, resources::NetworkMapping},
+    resources::ASSETS_DIR,
+};
+
+/// Ships are drawn brighter than white so they glow with bloom, tints keep that.
+const SHIP_BRIGHTNESS: f32 = 3.0;
+const ICON_SIZE: f32 = 12.0;
+const ICON_SPACING: f32 = 16.0;
+/// Above the ship, clear of its sprite.
+const ICON_OFFSET: f32 = 36.0;
+const ICON_Z: f32 = 5.0;
+
+#[derive(Resource)]
+struct StatusIcons(HashMap<String, Handle<Image>>);
+
+/// Status effects on a player as last reported by the server, `remaining` counted down locally.
+#[derive(Debug, Default, Component)]
+pub struct StatusEffects(pub Vec<StatusStack>);
+
+/// Icon of one of `ship`'s status effects, kept in a row above it.
+#[derive(Component)]
+struct StatusIcon {
+    ship: Entity,
+    offset: f32,
+}
+
+pub struct ClientStatusPlugin;
+impl Plugin for ClientStatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(load_status_effects);
+
+        app.add_system(sync_status.run_if(in_game()));
+        app.add_systems(
+            (count_down_status, tint_ships, update_icons, follow_ships)
+                .chain()
+                .after(sync_status),
+        );
+    }
+}
+
+fn load_status_effects(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let asset_path = Path::new(ASSETS_DIR);
+
+    // Only used for looks and the HUD, the server decides what an effect does
+    let defs = StatusDefs::load(&asset_path.join(STATUS_EFFECTS_FILE)).unwrap_or_else(|e| {
+        error!("Failed to load status effects, they won't be shown: {e}");
+        StatusDefs::default()
+    });
+
+    commands.insert_resource(StatusIcons(
+        defs.effects
+            .iter()
+            .map(|effect| {
+                let icon = asset_server.load(asset_path.join(&effect.icon));
+                (effect.id.clone(), icon)
+            })
+            .collect(),
+    ));
+    commands.insert_resource(defs);
+}
+
+fn sync_status(
+    mut commands: Commands,
+    mut server_messages: EventReader<ServerMessage>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    for message in server_messages.iter() {
+        let ServerMessage::PlayerStatus { entity, effects } = message else {
+            continue;
+        };
+
+        if let Some(client_entity) = network_mapping.0.get(entity) {
+            commands
+                .entity(*client_entity)
+                .insert(StatusEffects(effects.clone()));
+        }
+    }
+}
+
+fn count_down_status(mut players: Query<&mut StatusEffects>, time: Res<Time>) {
+    for mut effects in players.iter_mut() {
+        if effects.0.is_empty() {
+            continue;
+        }
+
+        // Only what the server sends counts as a change, the icons are laid out again on those
+        for effect in effects.bypass_change_detection().0.iter_mut() {
+            effect.remaining = (effect.remaining - time.delta_seconds()).max(0.0);
+        }
+    }
+}
+
+/// Blends the colors of every active effect over the ship, back to plain once they run out.
+fn tint_ships(
+    mut players: Query<(&StatusEffects, &mut Sprite), Changed<StatusEffects>>,
+    defs: Res<StatusDefs>,
+) {
+    for (effects, mut sprite) in players.iter_mut() {
+        let colors: Vec<Vec3> = effects
+            .0
+            .iter()
+            .filter_map(|effect| defs.get(&effect.id))
+            .map(|def| Vec3::from(def.color))
+            .collect();
+
+        let tint = match colors.len() {
+            0 => Vec3::ONE,
+            count => colors.into_iter().sum::<Vec3>() / count as f32,
+        } * SHIP_BRIGHTNESS;
+
+        sprite.color = Color::rgb(tint.x, tint.y, tint.z);
+    }
+}
+
+/// Lays out one icon per effect whenever a ship's effects change.
+fn update_icons(
+    mut commands: Commands,
+    players: Query<(Entity, &StatusEffects), Changed<StatusEffects>>,
+    icons: Query<(Entity, &StatusIcon)>,
+    defs: Res<StatusDefs>,
+    status_icons: Res<StatusIcons>,
+) {
+    for (ship, effects) in players.iter() {
+        for (entity, icon) in icons.iter() {
+            if icon.ship == ship {
+                commands.entity(entity).despawn();
+            }
+        }
+
+        let count = effects.0.len();
+        for (index, effect) in effects.0.iter().enumerate() {
+            let (Some(def), Some(texture)) = (defs.get(&effect.id), status_icons.0.get(&effect.id))
+            else {
+                continue;
+            };
+
+            let [r, g, b] = def.color;
+            commands
+                .spawn(SpriteBundle {
+                    texture: texture.clone(),
+                    sprite: Sprite {
+                        color: Color::rgb(r, g, b),
+                        custom_size: Some(Vec2::splat(ICON_SIZE)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .insert(StatusIcon {
+                    ship,
+                    offset: (index as f32 - (count - 1) as f32 / 2.0) * ICON_SPACING,
+                });
+        }
+    }
+}
+
+/// Icons don't turn with the ship, and go away with it.
+fn follow_ships(
+    mut commands: Commands,
+    mut icons: Query<(Entity, &StatusIcon, &mut Transform, &mut Visibility)>,
+    ships: Query<(&Transform, &Visibility), Without<StatusIcon>>,
+) {
+    for (entity, icon, mut transform, mut visibility) in icons.iter_mut() {
+        let Ok((ship_transform, ship_visibility)) = ships.get(icon.ship) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        transform.translation =
+            ship_transform.translation + Vec3::new(icon.offset, ICON_OFFSET, ICON_Z);
+        *visibility = *ship_visibility;
+    }
+}
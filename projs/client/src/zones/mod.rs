@@ -0,0 +1,183 @@
+use std::{f32::consts::FRAC_PI_2, path::Path};
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use blitz_common::{MapData, ServerMessage, ZoneKind};
+
+use crate::{networking::in_game, resources::ASSETS_DIR};
+
+/// Over the tiles, see-through enough for ships inside to show.
+const ZONE_Z: f32 = 0.5;
+const ZONE_ALPHA: f32 = 0.2;
+/// Alpha a zone flashes up to when set off.
+const FLASH_ALPHA: f32 = 0.6;
+const FLASH_TIME: f32 = 0.4;
+const ARROW_SIZE: f32 = 24.0;
+/// Relative to the assets directory
+const ARROW_SPRITE: &str = "laser_a_01.png";
+const TRIGGER_SOUND: &str = "enemy_laser.ogg";
+
+#[derive(Resource)]
+struct ZoneAssets {
+    arrow: Handle<Image>,
+    sound: Handle<AudioSource>,
+}
+
+/// One of the current level's zones, `index` matching the server's.
+#[derive(Component)]
+struct ZoneSprite {
+    index: u16,
+    /// The other end of a teleporter, lights up along with it
+    linked: Option<u16>,
+    /// Full strength, drawn at `ZONE_ALPHA` unless flashing
+    color: Color,
+}
+
+/// Fades a zone back from bright after it was set off.
+#[derive(Component)]
+struct ZoneFlash(Timer);
+
+pub struct ClientZonesPlugin;
+impl Plugin for ClientZonesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(load_zone_assets);
+
+        app.add_system(draw_zones.run_if(resource_changed::<LevelSelection>()));
+        app.add_system(zone_feedback.run_if(in_game()));
+        app.add_system(flash_zones);
+    }
+}
+
+fn load_zone_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let asset_path = Path::new(ASSETS_DIR);
+
+    commands.insert_resource(ZoneAssets {
+        arrow: asset_server.load(asset_path.join(ARROW_SPRITE)),
+        sound: asset_server.load(asset_path.join(TRIGGER_SOUND)),
+    });
+}
+
+fn zone_color(kind: &ZoneKind) -> Color {
+    match kind {
+        ZoneKind::Damage { .. } => Color::rgb(1.0, 0.35, 0.1),
+        ZoneKind::Slow { .. } => Color::rgb(0.5, 0.9, 0.4),
+        ZoneKind::BoostPad { .. } => Color::rgb(0.3, 0.7, 1.0),
+        ZoneKind::Teleporter { .. } => Color::rgb(0.8, 0.3, 1.0),
+    }
+}
+
+/// Replaces the zones drawn for the previous level, whether the server or the menu picked it.
+fn draw_zones(
+    mut commands: Commands,
+    zones: Query<Entity, With<ZoneSprite>>,
+    selection: Res<LevelSelection>,
+    map: Res<MapData>,
+    assets: Res<ZoneAssets>,
+) {
+    for entity in zones.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let level = match selection.as_ref() {
+        LevelSelection::Identifier(identifier) => map.level(identifier),
+        LevelSelection::Index(index) => map.levels.get(*index),
+        _ => None,
+    };
+    let Some(level) = level else {
+        return;
+    };
+
+    for (index, zone) in level.zones().enumerate() {
+        // The server already complains about broken zones, and won't set them off either
+        let Ok(zone) = zone else {
+            continue;
+        };
+
+        let color = zone_color(&zone.kind);
+
+        let mut entity = commands.spawn(SpriteBundle {
+            transform: Transform::from_translation(zone.position.extend(ZONE_Z)),
+            sprite: Sprite {
+                color: color.with_a(ZONE_ALPHA),
+                custom_size: Some(zone.size),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        entity.insert(ZoneSprite {
+            index: index as u16,
+            linked: level
+                .teleporter_destination(index)
+                .map(|linked| linked as u16),
+            color,
+        });
+
+        // Points where a pad launches to
+        if let ZoneKind::BoostPad { impulse } = zone.kind {
+            entity.with_children(|parent| {
+                parent.spawn(SpriteBundle {
+                    texture: assets.arrow.clone(),
+                    transform: Transform::from_rotation(Quat::from_rotation_z(
+                        impulse.y.atan2(impulse.x) - FRAC_PI_2,
+                    ))
+                    .with_translation(Vec3::Z * 0.1),
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::splat(ARROW_SIZE)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                });
+            });
+        }
+    }
+}
+
+fn zone_feedback(
+    mut commands: Commands,
+    mut server_messages: EventReader<ServerMessage>,
+    zones: Query<(Entity, &ZoneSprite)>,
+    assets: Res<ZoneAssets>,
+    audio: Res<Audio>,
+) {
+    for message in server_messages.iter() {
+        let ServerMessage::ZoneTriggered { zone, entity } = message else {
+            continue;
+        };
+        debug!(zone, player = ?entity, "Zone triggered");
+
+        let Some(linked) = zones
+            .iter()
+            .find(|(_, sprite)| sprite.index == *zone)
+            .map(|(_, sprite)| sprite.linked)
+        else {
+            continue;
+        };
+
+        for (entity, sprite) in zones.iter() {
+            if sprite.index == *zone || Some(sprite.index) == linked {
+                commands
+                    .entity(entity)
+                    .insert(ZoneFlash(Timer::from_seconds(FLASH_TIME, TimerMode::Once)));
+            }
+        }
+
+        audio.play(assets.sound.clone());
+    }
+}
+
+fn flash_zones(
+    mut commands: Commands,
+    mut zones: Query<(Entity, &ZoneSprite, &mut ZoneFlash, &mut Sprite)>,
+    time: Res<Time>,
+) {
+    for (entity, zone, mut flash, mut sprite) in zones.iter_mut() {
+        flash.0.tick(time.delta());
+
+        let alpha = ZONE_ALPHA + (FLASH_ALPHA - ZONE_ALPHA) * flash.0.percent_left();
+        sprite.color = zone.color.with_a(alpha);
+
+        if flash.0.finished() {
+            commands.entity(entity).remove::<ZoneFlash>();
+        }
+    }
+}
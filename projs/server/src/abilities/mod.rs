@@ -0,0 +1,326 @@
+use std::path::Path;
+
+use bevy::{math::vec2, prelude::*};
+use bevy_renet::renet::RenetServer;
+use blitz_common::{
+    AbilityDefs, AbilityEffect, Player, PlayerCommand, PlayerInput, ServerMessage, ShipDef,
+    Velocity, ABILITIES_FILE,
+};
+
+use crate::{
+    config::ASSETS_DIR,
+    health::{Dead, PlayerKilled},
+    networking::{resources::NetworkStats, send_message},
+    status::{ApplyStatus, StatusEffects},
+    weapons::spawn_projectiles,
+};
+
+/// An ability command a player sent, forwarded by the networking plugin.
+#[derive(Debug, Clone, Copy)]
+pub struct AbilityCommand {
+    pub entity: Entity,
+    pub command: PlayerCommand,
+}
+
+/// Replaces the ship's own movement while a dash lasts.
+#[derive(Debug, Component)]
+pub struct Dashing(pub Vec2);
+
+/// Takes damage in place of the hull.
+#[derive(Debug, Component)]
+pub struct Shield {
+    pub remaining: f32,
+}
+
+impl Shield {
+    /// Soaks up as much of `amount` as it can, returns the damage that gets through.
+    pub fn absorb(&mut self, amount: f32) -> f32 {
+        let absorbed = amount.min(self.remaining);
+        self.remaining -= absorbed;
+        amount - absorbed
+    }
+}
+
+#[derive(Debug, Default)]
+struct AbilitySlot {
+    /// Seconds until the ability can be used again
+    cooldown: f32,
+    /// Seconds left of its effect
+    active: f32,
+    /// Seconds it has been held for, charged abilities only
+    charge: Option<f32>,
+}
+
+/// Energy and ability state of a player, one slot per ability definition.
+#[derive(Debug, Component)]
+pub struct Abilities {
+    energy: f32,
+    slots: Vec<AbilitySlot>,
+}
+
+impl Abilities {
+    fn new(defs: &AbilityDefs, ship: &ShipDef) -> Self {
+        Self {
+            energy: ship.energy,
+            slots: defs
+                .abilities
+                .iter()
+                .map(|_| AbilitySlot::default())
+                .collect(),
+        }
+    }
+}
+
+pub struct ServerAbilitiesPlugin;
+impl Plugin for ServerAbilitiesPlugin {
+    fn build(&self, app: &mut App) {
+        let path = Path::new(ASSETS_DIR).join(ABILITIES_FILE);
+        let defs = AbilityDefs::load(&path).unwrap_or_else(|e| {
+            error!("Failed to load abilities, players will have none: {e}");
+            AbilityDefs::default()
+        });
+        info!("Loaded {} ability(ies)", defs.abilities.len());
+
+        app.insert_resource(defs);
+        app.add_event::<AbilityCommand>();
+
+        app.add_systems(
+            (
+                equip_abilities,
+                reset_abilities,
+                update_abilities,
+                handle_ability_commands,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn send_status(
+    server: &mut RenetServer,
+    stats: &mut NetworkStats,
+    client_id: u64,
+    abilities: &Abilities,
+) {
+    let message = ServerMessage::AbilityStatus {
+        energy: abilities.energy,
+        cooldowns: abilities.slots.iter().map(|slot| slot.cooldown).collect(),
+        active: abilities.slots.iter().map(|slot| slot.active).collect(),
+    };
+    send_message(server, stats, client_id, &message);
+}
+
+fn equip_abilities(
+    mut commands: Commands,
+    new_players: Query<(Entity, &Player), Added<Player>>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    defs: Res<AbilityDefs>,
+    ship: Res<ShipDef>,
+) {
+    for (entity, player) in new_players.iter() {
+        let abilities = Abilities::new(&defs, &ship);
+        send_status(&mut server, &mut stats, player.id, &abilities);
+        commands.entity(entity).insert(abilities);
+    }
+}
+
+/// Dying ends every effect and starts the next life with full energy and no cooldowns.
+fn reset_abilities(
+    mut commands: Commands,
+    mut player_killed: EventReader<PlayerKilled>,
+    mut players: Query<(&Player, &mut Abilities)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    defs: Res<AbilityDefs>,
+    ship: Res<ShipDef>,
+) {
+    for killed in player_killed.iter() {
+        let Ok((player, mut abilities)) = players.get_mut(killed.victim) else {
+            continue;
+        };
+
+        *abilities = Abilities::new(&defs, &ship);
+        send_status(&mut server, &mut stats, player.id, &abilities);
+        commands
+            .entity(killed.victim)
+            .remove::<Dashing>()
+            .remove::<Shield>();
+    }
+}
+
+fn update_abilities(
+    mut commands: Commands,
+    mut players: Query<(Entity, &Player, &mut Abilities, Option<&Shield>)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    defs: Res<AbilityDefs>,
+    ship: Res<ShipDef>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+
+    for (entity, player, mut abilities, shield) in players.iter_mut() {
+        abilities.energy = (abilities.energy + ship.energy_regen * delta).min(ship.energy);
+
+        let mut changed = false;
+        for (slot, def) in abilities.slots.iter_mut().zip(&defs.abilities) {
+            if let Some(charge) = &mut slot.charge {
+                *charge += delta;
+            }
+
+            if slot.cooldown > 0.0 {
+                slot.cooldown = (slot.cooldown - delta).max(0.0);
+                changed |= slot.cooldown == 0.0;
+            }
+
+            if slot.active <= 0.0 {
+                continue;
+            }
+
+            slot.active = (slot.active - delta).max(0.0);
+            let broken = matches!(def.effect, AbilityEffect::Shield { .. })
+                && shield.is_none_or(|shield| shield.remaining <= 0.0);
+            if slot.active > 0.0 && !broken {
+                continue;
+            }
+
+            slot.active = 0.0;
+            changed = true;
+            match def.effect {
+                AbilityEffect::Dash { .. } => {
+                    commands.entity(entity).remove::<Dashing>();
+                }
+                AbilityEffect::Shield { .. } => {
+                    commands.entity(entity).remove::<Shield>();
+                }
+                AbilityEffect::Status { .. } | AbilityEffect::ChargedShot { .. } => {}
+            }
+        }
+
+        if changed {
+            send_status(&mut server, &mut stats, player.id, &abilities);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn handle_ability_commands(
+    mut commands: Commands,
+    mut ability_commands: EventReader<AbilityCommand>,
+    mut players: Query<
+        (
+            &Player,
+            &Transform,
+            &Velocity,
+            &PlayerInput,
+            &mut Abilities,
+            Option<&StatusEffects>,
+        ),
+        Without<Dead>,
+    >,
+    mut apply_status: EventWriter<ApplyStatus>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    defs: Res<AbilityDefs>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for request in ability_commands.iter() {
+        let Ok((player, transform, velocity, input, mut abilities, effects)) =
+            players.get_mut(request.entity)
+        else {
+            continue;
+        };
+
+        let (index, released) = match request.command {
+            PlayerCommand::ActivateAbility(index) => (index as usize, false),
+            PlayerCommand::ReleaseAbility(index) => (index as usize, true),
+            _ => continue,
+        };
+        let Some(def) = defs.abilities.get(index) else {
+            continue;
+        };
+
+        if released {
+            let Some(charge) = abilities.slots[index].charge.take() else {
+                continue;
+            };
+            let AbilityEffect::ChargedShot {
+                weapon,
+                charge_time,
+                min_charge,
+            } = &def.effect
+            else {
+                continue;
+            };
+
+            let charged = (charge / charge_time.max(f32::EPSILON)).clamp(*min_charge, 1.0);
+            let damage =
+                weapon.damage * charged * effects.map_or(1.0, StatusEffects::damage_multiplier);
+            debug!(ability = %def.id, charged, "Released charged ability");
+
+            spawn_projectiles(
+                &mut commands,
+                &mut server,
+                &mut stats,
+                &mut rng,
+                request.entity,
+                transform,
+                velocity.0,
+                weapon,
+                damage,
+            );
+
+            abilities.slots[index].cooldown = def.cooldown;
+            send_status(&mut server, &mut stats, player.id, &abilities);
+            continue;
+        }
+
+        let slot = &abilities.slots[index];
+        let ready = slot.cooldown <= 0.0 && slot.active <= 0.0 && slot.charge.is_none();
+        if !ready || abilities.energy < def.cost {
+            continue;
+        }
+
+        abilities.energy -= def.cost;
+        let slot = &mut abilities.slots[index];
+        debug!(ability = %def.id, "Used ability");
+
+        match &def.effect {
+            AbilityEffect::Dash { speed } => {
+                let x = (input.right as i8 - input.left as i8) as f32;
+                let y = (input.up as i8 - input.down as i8) as f32;
+                let direction = match vec2(x, y).normalize_or_zero() {
+                    Vec2::ZERO => (transform.rotation * Vec3::Y).truncate(),
+                    direction => direction,
+                };
+
+                commands
+                    .entity(request.entity)
+                    .insert(Dashing(direction * *speed));
+                slot.active = def.duration;
+                slot.cooldown = def.duration + def.cooldown;
+            }
+            AbilityEffect::Shield { absorb } => {
+                commands
+                    .entity(request.entity)
+                    .insert(Shield { remaining: *absorb });
+                slot.active = def.duration;
+                slot.cooldown = def.duration + def.cooldown;
+            }
+            AbilityEffect::Status { status } => {
+                apply_status.send(ApplyStatus {
+                    target: request.entity,
+                    status: status.clone(),
+                    source: Some(request.entity),
+                });
+                slot.cooldown = def.cooldown;
+            }
+            // Fired and put on cooldown once released
+            AbilityEffect::ChargedShot { .. } => slot.charge = Some(0.0),
+        }
+
+        send_status(&mut server, &mut stats, player.id, &abilities);
+    }
+}
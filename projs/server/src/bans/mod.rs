@@ -0,0 +1,286 @@
+use std::{
+    fs,
+    net::IpAddr,
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use serde::{Deserialize, Serialize};
+
+use crate::config::ServerSettings;
+
+/// How long a rejected client is kept around so it receives the ban reason.
+const REJECTION_GRACE: f32 = 0.5;
+
+pub struct ServerBansPlugin;
+impl Plugin for ServerBansPlugin {
+    fn build(&self, app: &mut App) {
+        let path = app.world.resource::<ServerSettings>().ban_list.clone();
+
+        app.insert_resource(BanList::load(path.into()));
+        app.init_resource::<PendingRejections>();
+
+        app.add_systems((reload_ban_list, disconnect_rejected_clients));
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum BanTarget {
+    /// Player name sent in the connection data. Clients pick their own name, so this only
+    /// keeps out players who don't bother renaming; `Address` bans are the ones enforced.
+    Player(String),
+    /// Single address (`10.0.0.7`) or CIDR block (`10.0.0.0/8`)
+    Address(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanEntry {
+    pub target: BanTarget,
+    pub reason: String,
+    /// Unix timestamp in seconds, `None` for a permanent ban
+    #[serde(default)]
+    pub expires: Option<u64>,
+}
+
+impl BanEntry {
+    fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    fn matches(&self, name: &str, addr: Option<IpAddr>) -> bool {
+        match &self.target {
+            BanTarget::Player(player) => player.eq_ignore_ascii_case(name),
+            BanTarget::Address(cidr) => match (Cidr::from_str(cidr), addr) {
+                (Ok(cidr), Some(addr)) => cidr.contains(addr),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Contents of the ban file, e.g. the following, where only the address ban holds against a
+/// player changing their name:
+///
+/// ```ron
+/// (
+///     bans: [
+///         (target: Player("griefer"), reason: "Spawn camping", expires: Some(1767225600)),
+///         (target: Address("203.0.113.0/24"), reason: "Ban evasion"),
+///     ],
+/// )
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BanFile {
+    bans: Vec<BanEntry>,
+}
+
+#[derive(Debug, Resource)]
+pub struct BanList {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    check_timer: Timer,
+    bans: Vec<BanEntry>,
+}
+
+impl BanList {
+    pub fn load(path: PathBuf) -> Self {
+        let mut ban_list = Self {
+            path,
+            modified: None,
+            check_timer: Timer::from_seconds(2.0, TimerMode::Repeating),
+            bans: Vec::new(),
+        };
+        ban_list.reload();
+        ban_list
+    }
+
+    fn reload(&mut self) {
+        self.modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        let Ok(contents) = fs::read_to_string(&self.path) else {
+            self.bans.clear();
+            return;
+        };
+
+        match ron::from_str::<BanFile>(&contents) {
+            Ok(ban_file) => {
+                info!(
+                    path = %self.path.display(),
+                    "Loaded {} ban(s)",
+                    ban_file.bans.len()
+                );
+                self.bans = ban_file.bans;
+            }
+            // Keep the previous bans around rather than letting everyone in
+            Err(e) => warn!(path = %self.path.display(), "Failed to parse ban list: {e}"),
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        modified != self.modified
+    }
+
+    /// Returns the active ban matching this player, if any.
+    pub fn find(&self, name: &str, addr: Option<IpAddr>) -> Option<&BanEntry> {
+        let now = unix_now();
+
+        self.bans
+            .iter()
+            .find(|ban| !ban.is_expired(now) && ban.matches(name, addr))
+    }
+}
+
+/// Clients that were told why they are rejected and are waiting to be dropped.
+#[derive(Debug, Default, Resource)]
+pub struct PendingRejections(Vec<(u64, Timer)>);
+
+impl PendingRejections {
+    pub fn push(&mut self, client_id: u64) {
+        self.0
+            .push((client_id, Timer::from_seconds(REJECTION_GRACE, TimerMode::Once)));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        match (self.addr, addr) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+
+        let addr = IpAddr::from_str(addr.trim()).map_err(|e| format!("{s}: {e}"))?;
+        let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .trim()
+                .parse::<u8>()
+                .map_err(|e| format!("{s}: {e}"))?,
+            None => max_prefix,
+        };
+
+        if prefix > max_prefix {
+            return Err(format!("{s}: prefix larger than {max_prefix}"));
+        }
+
+        Ok(Self { addr, prefix })
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+fn reload_ban_list(mut ban_list: ResMut<BanList>, time: Res<Time>) {
+    ban_list.check_timer.tick(time.delta());
+
+    if ban_list.check_timer.just_finished() && ban_list.is_stale() {
+        ban_list.reload();
+    }
+}
+
+fn disconnect_rejected_clients(
+    mut pending: ResMut<PendingRejections>,
+    mut server: ResMut<RenetServer>,
+    time: Res<Time>,
+) {
+    pending.0.retain_mut(|(client_id, timer)| {
+        timer.tick(time.delta());
+
+        if timer.finished() {
+            server.disconnect(*client_id);
+        }
+
+        !timer.finished()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::*;
+
+    fn cidr(s: &str) -> Cidr {
+        Cidr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn zero_prefix_matches_everything_of_its_family() {
+        assert!(cidr("0.0.0.0/0").contains(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7))));
+        assert!(cidr("::/0").contains(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+    }
+
+    #[test]
+    fn full_prefix_matches_a_single_address() {
+        let single = cidr("10.0.0.7/32");
+        assert!(single.contains(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 7))));
+        assert!(!single.contains(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 8))));
+        assert_eq!(cidr("10.0.0.7"), single);
+    }
+
+    #[test]
+    fn block_matches_its_range() {
+        let block = cidr("10.0.0.0/8");
+        assert!(block.contains(IpAddr::V4(Ipv4Addr::new(10, 255, 1, 2))));
+        assert!(!block.contains(IpAddr::V4(Ipv4Addr::new(11, 0, 0, 1))));
+    }
+
+    #[test]
+    fn ipv6_block() {
+        let block = cidr("2001:db8::/32");
+        assert!(block.contains("2001:db8:1::1".parse().unwrap()));
+        assert!(!block.contains("2001:db9::1".parse().unwrap()));
+        assert!(cidr("::1").contains(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+    }
+
+    #[test]
+    fn prefix_above_maximum_is_rejected() {
+        assert!(Cidr::from_str("10.0.0.0/33").is_err());
+        assert!(Cidr::from_str("::/129").is_err());
+        assert!(Cidr::from_str("10.0.0.0/x").is_err());
+    }
+
+    #[test]
+    fn families_never_match_each_other() {
+        assert!(!cidr("::/0").contains(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        assert!(!cidr("::ffff:0:0/96").contains(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))));
+        assert!(!cidr("0.0.0.0/0").contains(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+    }
+}
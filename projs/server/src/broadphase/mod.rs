@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use blitz_common::{Collider, Enemy, Player, SpatialGrid};
+
+use crate::health::Dead;
+
+/// Side of a broadphase cell, a few ships wide.
+const CELL_SIZE: f32 = 128.0;
+/// Slack added around every query, candidates are checked against their exact shape anyway.
+const MARGIN: f32 = 16.0;
+
+/// Where the ships are once they moved this frame, anything that can be hit or pick things up.
+/// Walls don't need one, the `CollisionGrid` already is a uniform grid.
+///
+/// Systems moving ships run before [`update_broadphase`] and the ones querying it after, ships
+/// jumping elsewhere in between have to report it through [`Broadphase::moved`].
+#[derive(Debug, Resource)]
+pub struct Broadphase {
+    ships: SpatialGrid,
+}
+
+impl Broadphase {
+    /// Ships whose collider might overlap the box of `size` centered on `center`.
+    /// Candidates still have to be checked against their current transform.
+    pub fn ships_near(&self, center: Vec2, size: Vec2) -> Vec<Entity> {
+        self.ships.query(center, size + MARGIN * 2.0)
+    }
+
+    /// Adds where a ship was teleported to since the index was built. The old entry stays, it
+    /// only hands out a candidate that fails the exact check.
+    pub fn moved(&mut self, entity: Entity, center: Vec2, bounds: Vec2) {
+        self.ships.insert(entity, center, bounds);
+    }
+}
+
+pub struct ServerBroadphasePlugin;
+impl Plugin for ServerBroadphasePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Broadphase {
+            ships: SpatialGrid::new(CELL_SIZE),
+        });
+
+        app.add_system(update_broadphase);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_broadphase(
+    mut broadphase: ResMut<Broadphase>,
+    ships: Query<(Entity, &Transform, &Collider), (Or<(With<Player>, With<Enemy>)>, Without<Dead>)>,
+) {
+    broadphase.ships.clear();
+
+    for (entity, transform, collider) in ships.iter() {
+        let center = transform.translation.truncate();
+        broadphase
+            .ships
+            .insert(entity, center, collider.bounds(transform));
+    }
+}
@@ -1,48 +1,196 @@
-use bevy::{math::vec2, prelude::*, sprite::collide_aabb::collide};
-use bevy_renet::renet::RenetServer;
-use blitz_common::{FromPlayer, Player, Projectile, ServerChannel, ServerMessage};
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use blitz_common::{
+    Collider, CollisionGrid, Enemy, FromPlayer, Player, Projectile, Team, Velocity,
+};
+
+use crate::{
+    broadphase::{update_broadphase, Broadphase},
+    config::{FriendlyFire, ServerSettings},
+    explosions::Explosion,
+    health::{DamageEvent, Dead},
+    projectiles::move_projectiles,
+    status::ApplyStatus,
+};
 
 pub struct ServerCollisionsPlugin;
 impl Plugin for ServerCollisionsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(projectile_hit_player);
+        // Projectiles spent on a wall are despawned before ships are checked, so they can't
+        // hit anything on the way out
+        app.add_systems(
+            (
+                projectile_hit_wall,
+                apply_system_buffers,
+                projectile_hit_player,
+            )
+                .chain()
+                .after(move_projectiles)
+                .after(update_broadphase),
+        );
+    }
+}
+
+fn projectile_hit_wall(
+    mut commands: Commands,
+    mut projectile_query: Query<(
+        Entity,
+        &mut Transform,
+        &mut Velocity,
+        &mut Projectile,
+        &Collider,
+        &FromPlayer,
+    )>,
+    mut explosions: EventWriter<Explosion>,
+    collision: Res<CollisionGrid>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_seconds();
+
+    for (projectile_entity, mut transform, mut velocity, mut projectile, collider, from_player) in
+        projectile_query.iter_mut()
+    {
+        if !collider.overlaps_grid(&transform, &collision) {
+            continue;
+        }
+
+        let step = velocity.0 * delta_seconds;
+        let previous = transform.translation - step.extend(0.0);
+
+        if projectile.bounces == 0 {
+            // Removing the projectile also tells the clients about it
+            commands.entity(projectile_entity).despawn();
+
+            // Set off just outside the wall so it doesn't shelter the blast from itself
+            if let Some(def) = projectile.explosion.take() {
+                explosions.send(Explosion {
+                    position: previous.truncate(),
+                    def,
+                    source: Some(from_player.entity),
+                });
+            }
+            continue;
+        }
+        projectile.bounces -= 1;
+
+        // Step back out of the wall and find out which way it was hit by moving along one axis
+        let blocked = |offset: Vec2| {
+            let moved = Transform {
+                translation: previous + offset.extend(0.0),
+                ..*transform
+            };
+            collider.overlaps_grid(&moved, &collision)
+        };
+        let (hit_x, hit_y) = (blocked(step * Vec2::X), blocked(step * Vec2::Y));
+
+        // Only a corner was clipped, it bounces straight back
+        let flip = match (hit_x, hit_y) {
+            (false, false) => Vec2::NEG_ONE,
+            (hit_x, hit_y) => Vec2::new(
+                if hit_x { -1.0 } else { 1.0 },
+                if hit_y { -1.0 } else { 1.0 },
+            ),
+        };
+        velocity.0 *= flip;
+
+        transform.translation = previous;
+        transform.rotation = Quat::from_rotation_z(velocity.0.y.atan2(velocity.0.x) - FRAC_PI_2);
     }
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn projectile_hit_player(
     mut commands: Commands,
-    projectile_query: Query<(Entity, &FromPlayer, &Transform), With<Projectile>>,
-    player_query: Query<(Entity, &Transform), With<Player>>,
-    mut server: ResMut<RenetServer>,
+    mut projectile_query: Query<(Entity, &FromPlayer, &Transform, &mut Projectile, &Collider)>,
+    player_query: Query<
+        (Entity, &Transform, &Collider),
+        (Or<(With<Player>, With<Enemy>)>, Without<Dead>),
+    >,
+    teams: Query<&Team>,
+    enemies: Query<(), With<Enemy>>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut apply_status: EventWriter<ApplyStatus>,
+    mut explosions: EventWriter<Explosion>,
+    broadphase: Res<Broadphase>,
+    settings: Res<ServerSettings>,
 ) {
-    for (projectile_entity, from_player, projectile_transform) in projectile_query.iter() {
-        for (player_entity, player_tranform) in player_query.iter() {
+    let friendly_fire = settings.teams.friendly_fire;
+
+    for (
+        projectile_entity,
+        from_player,
+        projectile_transform,
+        mut projectile,
+        projectile_collider,
+    ) in projectile_query.iter_mut()
+    {
+        let projectile_position = projectile_transform.translation.truncate();
+        let candidates = broadphase.ships_near(
+            projectile_position,
+            projectile_collider.bounds(projectile_transform),
+        );
+
+        for (player_entity, player_tranform, player_collider) in player_query.iter_many(candidates)
+        {
             if player_entity != from_player.entity
-                && collide(
-                    player_tranform.translation,
-                    vec2(32.0, 32.0),
-                    projectile_transform.translation,
-                    vec2(32.0, 32.0),
+                && !projectile.hit.contains(&player_entity)
+                && projectile_collider.intersects(
+                    projectile_transform,
+                    player_collider,
+                    player_tranform,
                 )
-                .is_some()
             {
+                // Enemies don't shoot each other
+                if enemies.contains(from_player.entity) && enemies.contains(player_entity) {
+                    continue;
+                }
+
+                let teammates = matches!(
+                    (teams.get(from_player.entity), teams.get(player_entity)),
+                    (Ok(shooter), Ok(target)) if shooter == target
+                );
+                if teammates && friendly_fire == FriendlyFire::Off {
+                    continue;
+                }
+
+                let target = if teammates && friendly_fire == FriendlyFire::Reflect {
+                    from_player.entity
+                } else {
+                    player_entity
+                };
+
+                damage_events.send(DamageEvent {
+                    target,
+                    amount: projectile.damage,
+                    source: Some(from_player.entity),
+                });
+
+                if let Some(status) = &projectile.status {
+                    apply_status.send(ApplyStatus {
+                        target,
+                        status: status.clone(),
+                        source: Some(from_player.entity),
+                    });
+                }
+
+                if projectile.pierce > 0 {
+                    projectile.pierce -= 1;
+                    projectile.hit.push(player_entity);
+                    continue;
+                }
+
+                // Removing the projectile also tells the clients about it
                 commands.entity(projectile_entity).despawn();
-                // commands.entity(player_entity).despawn();
-
-                let messages = vec![
-                    bincode::serialize(&ServerMessage::DespawnProjectile {
-                        entity: projectile_entity,
-                    })
-                    .unwrap(),
-                    bincode::serialize(&ServerMessage::DespawnPlayer {
-                        entity: player_entity,
-                    })
-                    .unwrap(),
-                ];
-
-                for message in messages {
-                    server.broadcast_message(ServerChannel::ServerMessages, message);
+
+                if let Some(def) = projectile.explosion.take() {
+                    explosions.send(Explosion {
+                        position: projectile_position,
+                        def,
+                        source: Some(from_player.entity),
+                    });
                 }
+                break;
             }
         }
     }
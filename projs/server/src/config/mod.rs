@@ -0,0 +1,345 @@
+use std::fs;
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+pub static ASSETS_DIR: &str = env!("ASSETS_DIR");
+
+pub const SERVER_SETTINGS_PATH: &str = "server.ron";
+
+/// Server configuration, read once at startup. Missing fields fall back to their defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+#[serde(default)]
+pub struct ServerSettings {
+    pub ban_list: String,
+    pub capacity: CapacitySettings,
+    pub enemies: EnemySettings,
+    pub log: LogSettings,
+    #[serde(rename = "match")]
+    pub match_rules: MatchSettings,
+    pub metrics: MetricsSettings,
+    pub navigation: NavigationSettings,
+    pub player: PlayerSettings,
+    pub query: QuerySettings,
+    pub spawns: SpawnSettings,
+    pub survival: SurvivalSettings,
+    pub teams: TeamSettings,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            ban_list: "bans.ron".to_string(),
+            capacity: CapacitySettings::default(),
+            enemies: EnemySettings::default(),
+            log: LogSettings::default(),
+            match_rules: MatchSettings::default(),
+            metrics: MetricsSettings::default(),
+            navigation: NavigationSettings::default(),
+            player: PlayerSettings::default(),
+            query: QuerySettings::default(),
+            spawns: SpawnSettings::default(),
+            survival: SurvivalSettings::default(),
+            teams: TeamSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CapacitySettings {
+    pub max_players: usize,
+    pub spectator_slots: usize,
+    /// Extra player slots only clients sending one of `admin_tokens` can take
+    pub reserved_admin_slots: usize,
+    /// Secrets admins set as `BLITZ_ADMIN_TOKEN` on their client, names are up to the client
+    /// and prove nothing
+    pub admin_tokens: Vec<String>,
+    pub max_queue_length: usize,
+}
+
+impl Default for CapacitySettings {
+    fn default() -> Self {
+        Self {
+            max_players: 16,
+            spectator_slots: 8,
+            reserved_admin_slots: 2,
+            admin_tokens: Vec::new(),
+            max_queue_length: 16,
+        }
+    }
+}
+
+impl CapacitySettings {
+    /// Connections the transport has to accept, queued clients included.
+    pub fn max_clients(&self) -> usize {
+        self.max_players + self.reserved_admin_slots + self.spectator_slots + self.max_queue_length
+    }
+
+    pub fn is_admin(&self, token: Option<&str>) -> bool {
+        token.is_some_and(|token| {
+            !token.is_empty() && self.admin_tokens.iter().any(|admin| admin == token)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnemySettings {
+    /// Enemies alive at once, 0 disables them
+    pub max_alive: usize,
+    /// Seconds between two spawns while below `max_alive`
+    pub spawn_interval: f32,
+    /// Enemies don't spawn closer than this to a player
+    pub min_player_distance: f32,
+}
+
+impl Default for EnemySettings {
+    fn default() -> Self {
+        Self {
+            max_alive: 4,
+            spawn_interval: 8.0,
+            min_player_distance: 400.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogSettings {
+    /// `EnvFilter` directives, e.g. `info,server::networking=debug`
+    pub filter: String,
+    /// Emit one JSON object per line instead of human readable output
+    pub json: bool,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            filter: "info".to_string(),
+            json: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchSettings {
+    /// Identifier of the game mode plugin, e.g. `deathmatch`
+    pub game_mode: String,
+    pub min_players: usize,
+    /// Seconds of warmup once enough players are in
+    pub warmup_time: f32,
+    pub countdown_time: f32,
+    /// Seconds a match lasts, `None` to only end on the score limit
+    pub time_limit: Option<f32>,
+    pub score_limit: Option<u32>,
+    pub post_match_time: f32,
+    /// Level identifiers played one after the other, empty for every level of the world in order
+    pub map_rotation: Vec<String>,
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        Self {
+            game_mode: "deathmatch".to_string(),
+            min_players: 2,
+            warmup_time: 20.0,
+            countdown_time: 5.0,
+            time_limit: Some(600.0),
+            score_limit: Some(25),
+            post_match_time: 10.0,
+            map_rotation: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsSettings {
+    pub enabled: bool,
+    pub address: String,
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "127.0.0.1:9100".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NavigationSettings {
+    /// Paths kept around before the cache starts over
+    pub cache_size: usize,
+    /// Send every computed path to the clients, can be toggled with `navdebug`
+    pub debug_paths: bool,
+}
+
+impl Default for NavigationSettings {
+    fn default() -> Self {
+        Self {
+            cache_size: 512,
+            debug_paths: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerSettings {
+    pub max_health: f32,
+    /// Seconds a dead player waits before respawning
+    pub respawn_delay: f32,
+    /// Seconds a respawned player can't take damage
+    pub respawn_invulnerability: f32,
+}
+
+impl Default for PlayerSettings {
+    fn default() -> Self {
+        Self {
+            max_health: 100.0,
+            respawn_delay: 3.0,
+            respawn_invulnerability: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuerySettings {
+    pub enabled: bool,
+    pub address: String,
+}
+
+impl Default for QuerySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            address: "127.0.0.1:5002".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SpawnSelection {
+    /// The point whose closest enemy is the farthest away
+    #[default]
+    FarthestFromEnemies,
+    /// A random point no enemy can see, the farthest one when every point is watched
+    AvoidLineOfSight,
+    Random,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpawnSettings {
+    pub selection: SpawnSelection,
+    /// Seed of the random picks, `None` for a different sequence every run
+    pub seed: Option<u64>,
+}
+
+impl Default for SpawnSettings {
+    fn default() -> Self {
+        Self {
+            selection: SpawnSelection::FarthestFromEnemies,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SurvivalRespawn {
+    /// Dead players wait for the wave to be cleared
+    #[default]
+    BetweenWaves,
+    /// Every death during a wave costs the team one of these, once they are gone
+    /// dead players wait for the wave to be cleared
+    SharedLives(u32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SurvivalSettings {
+    /// Enemies in the first wave with a single player
+    pub first_wave: usize,
+    /// Enemies added with every wave
+    pub wave_growth: usize,
+    /// Share of a wave added for every player past the first
+    pub per_player: f32,
+    /// Share of their health enemies gain with every wave
+    pub health_growth: f32,
+    /// Enemies of a wave alive at once, the rest come in as they die
+    pub max_alive: usize,
+    /// Seconds between two enemies of a wave coming in
+    pub spawn_interval: f32,
+    /// Seconds of rest before every wave
+    pub intermission: f32,
+    /// Every that many waves is a boss wave, 0 for none
+    pub boss_every: u32,
+    /// Enemy kind leading boss waves
+    pub boss: String,
+    pub respawn: SurvivalRespawn,
+    /// Waves to clear to win, `None` to go on until the players are overrun
+    pub waves: Option<u32>,
+}
+
+impl Default for SurvivalSettings {
+    fn default() -> Self {
+        Self {
+            first_wave: 4,
+            wave_growth: 2,
+            per_player: 0.5,
+            health_growth: 0.1,
+            max_alive: 8,
+            spawn_interval: 1.5,
+            intermission: 10.0,
+            boss_every: 5,
+            boss: "dreadnought".to_string(),
+            respawn: SurvivalRespawn::BetweenWaves,
+            waves: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FriendlyFire {
+    /// Projectiles fly through teammates
+    #[default]
+    Off,
+    On,
+    /// The shooter takes the damage instead
+    Reflect,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamSettings {
+    pub friendly_fire: FriendlyFire,
+    pub auto_balance: bool,
+}
+
+impl Default for TeamSettings {
+    fn default() -> Self {
+        Self {
+            friendly_fire: FriendlyFire::Off,
+            auto_balance: true,
+        }
+    }
+}
+
+impl ServerSettings {
+    pub fn load() -> Self {
+        match fs::read_to_string(SERVER_SETTINGS_PATH) {
+            // Logging isn't set up yet as it depends on these settings
+            Ok(contents) => ron::from_str(&contents).unwrap_or_else(|e| {
+                eprintln!("Failed to parse {SERVER_SETTINGS_PATH}, using defaults: {e}");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    io::{self, BufRead},
+    sync::{
+        mpsc::{self, Receiver},
+        Mutex,
+    },
+    thread,
+};
+
+use bevy::prelude::*;
+
+/// A line typed into the server's admin console, split into a command name and arguments.
+#[derive(Debug, Clone)]
+pub struct ConsoleCommand {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+#[derive(Resource)]
+struct ConsoleInput(Mutex<Receiver<String>>);
+
+pub struct ServerConsolePlugin;
+impl Plugin for ServerConsolePlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        app.insert_resource(ConsoleInput(Mutex::new(receiver)));
+        app.add_event::<ConsoleCommand>();
+
+        app.add_system(read_console_input.in_base_set(CoreSet::PreUpdate));
+        app.add_system(help_command);
+    }
+}
+
+fn read_console_input(input: Res<ConsoleInput>, mut console_commands: EventWriter<ConsoleCommand>) {
+    let Ok(receiver) = input.0.lock() else {
+        return;
+    };
+
+    for line in receiver.try_iter() {
+        let mut words = line.split_whitespace().map(str::to_string);
+
+        if let Some(name) = words.next() {
+            console_commands.send(ConsoleCommand {
+                name: name.to_lowercase(),
+                args: words.collect(),
+            });
+        }
+    }
+}
+
+fn help_command(mut console_commands: EventReader<ConsoleCommand>) {
+    for command in console_commands.iter() {
+        if command.name == "help" {
+            info!(
+                "Commands: status, queue, map <level>, nextmap [level], navdebug [on|off], log <filter>, shutdown [reason], restart <seconds> [reason]"
+            );
+        }
+    }
+}
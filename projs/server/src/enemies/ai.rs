@@ -0,0 +1,274 @@
+use std::{collections::VecDeque, f32::consts::FRAC_PI_2};
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use blitz_common::{CollisionGrid, Enemy, EnemyDefs, Health, Player, Velocity, PLAYER_SIZE};
+use rand::Rng;
+
+use crate::{
+    health::Dead, navigation::Navigation, networking::resources::NetworkStats,
+    spawning::Spawning, weapons::spawn_projectiles,
+};
+
+/// How far from its spawn an idle enemy wanders.
+const PATROL_RADIUS: f32 = 300.0;
+/// Share of the full speed used while patrolling.
+const PATROL_SPEED: f32 = 0.5;
+/// Distance at which a waypoint counts as reached.
+const WAYPOINT_REACHED: f32 = 8.0;
+/// Random points tried when looking for the next waypoint.
+const WAYPOINT_ATTEMPTS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnemyState {
+    /// Wanders around its spawn until a player shows up
+    Patrol,
+    /// Finds its way to where the target was last seen
+    Chase,
+    /// Holds still and shoots at the target in range
+    Attack,
+    /// Low on health, runs away from the target
+    Flee,
+}
+
+#[derive(Debug, Component)]
+pub struct EnemyBrain {
+    pub state: EnemyState,
+    target: Option<Entity>,
+    last_seen: Vec2,
+    /// Seconds since the target was last seen
+    unseen: f32,
+    home: Vec2,
+    /// Where the enemy is heading, reached by following `path`
+    destination: Option<Vec2>,
+    path: VecDeque<Vec2>,
+    /// Seconds until the enemy can fire again
+    cooldown: f32,
+}
+
+impl EnemyBrain {
+    pub fn new(home: Vec2) -> Self {
+        Self {
+            state: EnemyState::Patrol,
+            target: None,
+            last_seen: home,
+            unseen: 0.0,
+            home,
+            destination: None,
+            path: VecDeque::new(),
+            cooldown: 0.0,
+        }
+    }
+
+    /// Makes `target`, seen at `position`, the one to go after.
+    pub fn provoke(&mut self, target: Entity, position: Vec2) {
+        self.target = Some(target);
+        self.last_seen = position;
+        self.unseen = 0.0;
+    }
+}
+
+/// Picks every enemy's target and state from what it can see.
+#[allow(clippy::type_complexity)]
+pub fn think(
+    mut enemies: Query<(Entity, &Transform, &Health, &Enemy, &mut EnemyBrain)>,
+    players: Query<
+        (Entity, &Transform),
+        (With<Player>, Without<Dead>, Without<Spawning>, Without<Enemy>),
+    >,
+    defs: Res<EnemyDefs>,
+    collision: Res<CollisionGrid>,
+    time: Res<Time>,
+) {
+    for (entity, transform, health, enemy, mut brain) in enemies.iter_mut() {
+        let Some(def) = defs.get(&enemy.kind) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+
+        let visible = players
+            .iter()
+            .map(|(player, transform)| (player, transform.translation.truncate()))
+            .filter(|(_, player)| {
+                player.distance(position) <= def.sight_range
+                    && collision.line_of_sight(position, *player)
+            })
+            .min_by(|(_, a), (_, b)| {
+                a.distance_squared(position)
+                    .total_cmp(&b.distance_squared(position))
+            });
+
+        match visible {
+            Some((player, player_position)) => brain.provoke(player, player_position),
+            None => brain.unseen += time.delta_seconds(),
+        }
+
+        // Dead targets and the ones out of sight for too long are forgotten
+        let target_gone = brain.target.is_some_and(|target| players.get(target).is_err());
+        if target_gone || brain.unseen > def.memory {
+            brain.target = None;
+        }
+
+        let state = match brain.target {
+            None => EnemyState::Patrol,
+            Some(_) if health.current < health.max * def.flee_below => EnemyState::Flee,
+            Some(_)
+                if visible.is_some() && brain.last_seen.distance(position) <= def.attack_range =>
+            {
+                EnemyState::Attack
+            }
+            Some(_) => EnemyState::Chase,
+        };
+
+        if state != brain.state {
+            debug!(
+                network_id = ?entity,
+                from = ?brain.state,
+                to = ?state,
+                "Enemy changed state"
+            );
+            brain.state = state;
+            brain.destination = None;
+        }
+    }
+}
+
+/// Moves and fires according to each enemy's state.
+#[allow(clippy::too_many_arguments)]
+pub fn act(
+    mut commands: Commands,
+    mut enemies: Query<(
+        Entity,
+        &mut Transform,
+        &mut Velocity,
+        &Enemy,
+        &mut EnemyBrain,
+    )>,
+    defs: Res<EnemyDefs>,
+    collision: Res<CollisionGrid>,
+    mut navigation: ResMut<Navigation>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    time: Res<Time>,
+) {
+    let mut rng = rand::thread_rng();
+    let delta_seconds = time.delta_seconds();
+
+    for (entity, mut transform, mut velocity, enemy, mut brain) in enemies.iter_mut() {
+        let Some(def) = defs.get(&enemy.kind) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+        brain.cooldown = (brain.cooldown - delta_seconds).max(0.0);
+
+        let (direction, speed) = match brain.state {
+            EnemyState::Patrol => {
+                let reached = brain.destination.is_none_or(|destination| {
+                    destination.distance(position) < WAYPOINT_REACHED
+                });
+                let destination = match reached {
+                    true => patrol_waypoint(&collision, brain.home, &mut rng),
+                    false => brain.destination,
+                };
+
+                let direction = destination.map_or(Vec2::ZERO, |destination| {
+                    steer(&mut brain, &mut navigation, entity, position, destination)
+                });
+                (direction, def.speed * PATROL_SPEED)
+            }
+            EnemyState::Chase => {
+                let destination = brain.last_seen;
+                let direction = steer(&mut brain, &mut navigation, entity, position, destination);
+                (direction, def.speed)
+            }
+            EnemyState::Attack => (Vec2::ZERO, 0.0),
+            EnemyState::Flee => ((position - brain.last_seen).normalize_or_zero(), def.speed),
+        };
+
+        let delta = direction * speed * delta_seconds;
+        let moved = collision.move_and_slide(position, Vec2::splat(PLAYER_SIZE), delta);
+
+        // Stuck on a wall, plan again next frame
+        if moved.distance(position) < delta.length() / 2.0 {
+            brain.destination = None;
+        }
+
+        transform.translation.x = moved.x;
+        transform.translation.y = moved.y;
+        if delta_seconds > 0.0 {
+            velocity.0 = (moved - position) / delta_seconds;
+        }
+
+        let facing = match brain.state {
+            EnemyState::Attack => brain.last_seen - moved,
+            _ => direction,
+        };
+        if facing != Vec2::ZERO {
+            transform.rotation = Quat::from_rotation_z(facing.y.atan2(facing.x) - FRAC_PI_2);
+        }
+
+        if brain.state == EnemyState::Attack && brain.cooldown <= 0.0 {
+            brain.cooldown = def.weapon.cooldown();
+            spawn_projectiles(
+                &mut commands,
+                &mut server,
+                &mut stats,
+                &mut rng,
+                entity,
+                &transform,
+                velocity.0,
+                &def.weapon,
+                def.weapon.damage,
+            );
+        }
+    }
+}
+
+/// Direction towards the next waypoint on the way to `destination`.
+/// Only plans a new path when the destination moved to another cell.
+fn steer(
+    brain: &mut EnemyBrain,
+    navigation: &mut Navigation,
+    entity: Entity,
+    position: Vec2,
+    destination: Vec2,
+) -> Vec2 {
+    let replan = brain.destination.is_none_or(|previous| {
+        previous.distance(destination) >= navigation.cell_size()
+    });
+
+    if replan {
+        brain.destination = Some(destination);
+        brain.path = navigation
+            .find_path(entity, position, destination)
+            .map(|path| path.iter().copied().collect())
+            .unwrap_or_default();
+    }
+
+    while let Some(next) = brain.path.front() {
+        if next.distance(position) >= WAYPOINT_REACHED {
+            break;
+        }
+        brain.path.pop_front();
+    }
+
+    // The path ends in the center of the destination's cell, cover the rest in a straight line
+    let next = brain.path.front().copied().unwrap_or(destination);
+    if next.distance(position) < WAYPOINT_REACHED {
+        return Vec2::ZERO;
+    }
+    (next - position).normalize_or_zero()
+}
+
+/// A free spot around `home` for a patrol to head to.
+fn patrol_waypoint(collision: &CollisionGrid, home: Vec2, rng: &mut impl Rng) -> Option<Vec2> {
+    (0..WAYPOINT_ATTEMPTS)
+        .map(|_| {
+            let offset = Vec2::new(
+                rng.gen_range(-PATROL_RADIUS..=PATROL_RADIUS),
+                rng.gen_range(-PATROL_RADIUS..=PATROL_RADIUS),
+            );
+            home + offset
+        })
+        .find(|waypoint| !collision.overlaps(*waypoint, Vec2::splat(PLAYER_SIZE)))
+}
@@ -0,0 +1,320 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_renet::renet::{RenetServer, ServerEvent};
+use blitz_common::{
+    Enemy, EnemyDef, EnemyDefs, Health, MapData, Player, ServerMessage, Velocity, ENEMIES_FILE,
+    ENEMY_SPAWN_ENTITY,
+};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    broadphase::update_broadphase,
+    config::{ServerSettings, ASSETS_DIR},
+    game_modes::ActiveGameMode,
+    health::{DamageEvent, Dead},
+    map::CurrentLevel,
+    networking::{broadcast_message, resources::NetworkStats, send_message},
+    spawning::Spawning,
+    waves::SURVIVAL,
+};
+
+mod ai;
+
+pub use ai::EnemyBrain;
+
+/// Where enemies come from on the current level.
+#[derive(Debug, Clone)]
+struct EnemySpawn {
+    position: Vec2,
+    /// Only this kind spawns here, any kind when `None`
+    kind: Option<String>,
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct EnemySpawns(Vec<EnemySpawn>);
+
+impl EnemySpawns {
+    /// A random spawn an enemy of `kind` may come from, away from `players` when there is one.
+    pub fn pick(
+        &self,
+        kind: &str,
+        players: &[Vec2],
+        min_distance: f32,
+        rng: &mut impl Rng,
+    ) -> Option<Vec2> {
+        let allowed: Vec<_> = self
+            .0
+            .iter()
+            .filter(|spawn| spawn.kind.as_deref().is_none_or(|only| only == kind))
+            .collect();
+        let away: Vec<_> = allowed
+            .iter()
+            .copied()
+            .filter(|spawn| {
+                players
+                    .iter()
+                    .all(|player| player.distance(spawn.position) >= min_distance)
+            })
+            .collect();
+
+        away.choose(rng)
+            .or_else(|| allowed.choose(rng))
+            .map(|spawn| spawn.position)
+    }
+}
+
+/// Sent when an enemy's health drops to zero, right before it is despawned.
+#[derive(Debug, Clone)]
+pub struct EnemyKilled {
+    pub enemy: Entity,
+    pub kind: String,
+}
+
+#[derive(Debug, Resource)]
+struct EnemySpawnTimer(Timer);
+
+pub struct ServerEnemiesPlugin;
+impl Plugin for ServerEnemiesPlugin {
+    fn build(&self, app: &mut App) {
+        let path = Path::new(ASSETS_DIR).join(ENEMIES_FILE);
+        let defs = EnemyDefs::load(&path).unwrap_or_else(|e| {
+            error!("Failed to load enemies, none will spawn: {e}");
+            EnemyDefs::default()
+        });
+        info!("Loaded {} enemy kind(s)", defs.enemies.len());
+
+        let interval = app.world.resource::<ServerSettings>().enemies.spawn_interval;
+
+        app.insert_resource(defs);
+        app.init_resource::<EnemySpawns>();
+        app.add_event::<EnemyKilled>();
+        app.insert_resource(EnemySpawnTimer(Timer::from_seconds(
+            interval,
+            TimerMode::Repeating,
+        )));
+
+        app.add_system(reset_enemies.run_if(resource_changed::<CurrentLevel>()));
+        app.add_systems(
+            (spawn_enemies.run_if(ambient_spawns), ai::think, ai::act)
+                .chain()
+                .before(update_broadphase),
+        );
+        app.add_systems((damage_enemies, sync_enemies));
+    }
+}
+
+/// Removes the enemies of the previous level and collects the spawns of the current one.
+#[allow(clippy::too_many_arguments)]
+fn reset_enemies(
+    mut commands: Commands,
+    enemies: Query<Entity, With<Enemy>>,
+    mut spawns: ResMut<EnemySpawns>,
+    mut timer: ResMut<EnemySpawnTimer>,
+    current: Res<CurrentLevel>,
+    map: Res<MapData>,
+    defs: Res<EnemyDefs>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for entity in enemies.iter() {
+        commands.entity(entity).despawn();
+
+        let message = ServerMessage::DespawnEnemy { entity };
+        broadcast_message(&mut server, &mut stats, &message);
+    }
+
+    timer.0.reset();
+    spawns.0.clear();
+
+    let Some(level) = map.level(&current.identifier) else {
+        return;
+    };
+
+    for spawn in level.entities(ENEMY_SPAWN_ENTITY) {
+        let kind = spawn.field_str("enemy").map(str::to_string);
+
+        if let Some(kind) = kind.as_ref().filter(|kind| defs.get(kind).is_none()) {
+            warn!(level = %level.identifier, enemy = %kind, "Unknown enemy on the map");
+            continue;
+        }
+
+        spawns.0.push(EnemySpawn {
+            position: spawn.position,
+            kind,
+        });
+    }
+
+    if spawns.0.is_empty() {
+        info!(level = %level.identifier, "Level has no enemy spawns");
+    }
+}
+
+/// Survival brings its own enemies in waves.
+fn ambient_spawns(mode: Res<ActiveGameMode>) -> bool {
+    mode.id != SURVIVAL
+}
+
+/// Spawns an enemy of kind `def` with `health` and tells the clients about it.
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    server: &mut RenetServer,
+    stats: &mut NetworkStats,
+    def: &EnemyDef,
+    position: Vec2,
+    health: f32,
+) -> Entity {
+    let entity = commands
+        .spawn(Transform::from_translation(position.extend(0.0)))
+        .insert(Enemy {
+            kind: def.id.clone(),
+        })
+        .insert(Health::new(health))
+        .insert(def.collider)
+        .insert(Velocity::default())
+        .insert(EnemyBrain::new(position))
+        .id();
+    debug!(network_id = ?entity, enemy = %def.id, "Spawned enemy");
+
+    let message = ServerMessage::SpawnEnemy {
+        entity,
+        enemy: def.id.clone(),
+        position,
+    };
+    broadcast_message(server, stats, &message);
+
+    entity
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn spawn_enemies(
+    mut commands: Commands,
+    mut timer: ResMut<EnemySpawnTimer>,
+    spawns: Res<EnemySpawns>,
+    enemies: Query<(), With<Enemy>>,
+    players: Query<&Transform, (With<Player>, Without<Dead>, Without<Spawning>)>,
+    defs: Res<EnemyDefs>,
+    settings: Res<ServerSettings>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    time: Res<Time>,
+) {
+    let rules = &settings.enemies;
+
+    // Nobody to fight, don't fill the map for no one
+    if rules.max_alive == 0 || players.is_empty() {
+        return;
+    }
+
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() || enemies.iter().count() >= rules.max_alive {
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+
+    let candidates: Vec<_> = spawns
+        .0
+        .iter()
+        .filter(|spawn| {
+            players.iter().all(|transform| {
+                transform.translation.truncate().distance(spawn.position)
+                    >= rules.min_player_distance
+            })
+        })
+        .collect();
+    let Some(spawn) = candidates.choose(&mut rng) else {
+        return;
+    };
+
+    // The survival boss only shows up on its own waves
+    let def = match &spawn.kind {
+        Some(kind) => defs.get(kind),
+        None => {
+            let regulars: Vec<_> = defs
+                .enemies
+                .iter()
+                .filter(|def| def.id != settings.survival.boss)
+                .collect();
+            regulars.choose(&mut rng).copied()
+        }
+    };
+    let Some(def) = def else {
+        return;
+    };
+
+    spawn_enemy(
+        &mut commands,
+        &mut server,
+        &mut stats,
+        def,
+        spawn.position,
+        def.health,
+    );
+}
+
+fn damage_enemies(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut enemy_killed: EventWriter<EnemyKilled>,
+    mut enemies: Query<(&Enemy, &mut Health, &mut EnemyBrain)>,
+    players: Query<&Transform, With<Player>>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for damage in damage_events.iter() {
+        let Ok((enemy, mut health, mut brain)) = enemies.get_mut(damage.target) else {
+            continue;
+        };
+
+        // Several hits can land in the same frame, the enemy is only removed once
+        if health.is_dead() {
+            continue;
+        }
+
+        health.current = (health.current - damage.amount).max(0.0);
+
+        // Getting shot gives away where the shooter is
+        if let Some(source) = damage.source {
+            if let Ok(transform) = players.get(source) {
+                brain.provoke(source, transform.translation.truncate());
+            }
+        }
+
+        if health.is_dead() {
+            debug!(network_id = ?damage.target, killer = ?damage.source, "Enemy killed");
+
+            commands.entity(damage.target).despawn();
+
+            enemy_killed.send(EnemyKilled {
+                enemy: damage.target,
+                kind: enemy.kind.clone(),
+            });
+
+            let message = ServerMessage::DespawnEnemy {
+                entity: damage.target,
+            };
+            broadcast_message(&mut server, &mut stats, &message);
+        }
+    }
+}
+
+fn sync_enemies(
+    mut server_events: EventReader<ServerEvent>,
+    enemies: Query<(Entity, &Transform, &Enemy)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for event in server_events.iter() {
+        if let ServerEvent::ClientConnected(id, _) = event {
+            for (entity, transform, enemy) in enemies.iter() {
+                let message = ServerMessage::SpawnEnemy {
+                    entity,
+                    enemy: enemy.kind.clone(),
+                    position: transform.translation.truncate(),
+                };
+                send_message(&mut server, &mut stats, *id, &message);
+            }
+        }
+    }
+}
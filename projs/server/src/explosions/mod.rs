@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use blitz_common::{
+    Collider, CollisionGrid, Enemy, ExplosionDef, Player, ServerMessage, Team, PLAYER_SIZE,
+};
+
+use crate::{
+    broadphase::{update_broadphase, Broadphase},
+    config::{FriendlyFire, ServerSettings},
+    health::{DamageEvent, Dead},
+    networking::{broadcast_message, resources::NetworkStats},
+};
+
+/// Share of the knockback speed lost every second.
+const KNOCKBACK_DRAG: f32 = 6.0;
+/// Knockback slower than this in pixels per second is over.
+const KNOCKBACK_STOP: f32 = 10.0;
+
+/// A blast at `position`, `source` getting the credit for whatever it kills.
+#[derive(Debug, Clone, Copy)]
+pub struct Explosion {
+    pub position: Vec2,
+    pub def: ExplosionDef,
+    pub source: Option<Entity>,
+}
+
+/// Pushes `target` at `velocity`, slowing down like a blast would.
+#[derive(Debug, Clone, Copy)]
+pub struct Knockback {
+    pub target: Entity,
+    pub velocity: Vec2,
+}
+
+/// Velocity ships were blasted away with, on top of their own movement.
+/// Kept here rather than on the ships since a blast can kill what it pushes.
+#[derive(Debug, Default, Resource)]
+struct Knockbacks(HashMap<Entity, Vec2>);
+
+pub struct ServerExplosionsPlugin;
+impl Plugin for ServerExplosionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Knockbacks>();
+        app.add_event::<Explosion>();
+        app.add_event::<Knockback>();
+
+        // Knockback from this frame's blasts moves ships on the next one, after which the
+        // broadphase catches up with them
+        app.add_systems((
+            apply_knockback.before(update_broadphase),
+            explode.after(update_broadphase),
+        ));
+    }
+}
+
+/// Hurts and pushes every ship in reach, its owner included, the closer to the center the
+/// harder. Reach is measured to the edge of a ship's collider rather than its center.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn explode(
+    mut explosions: EventReader<Explosion>,
+    ships: Query<(Entity, &Transform, &Collider), (Or<(With<Player>, With<Enemy>)>, Without<Dead>)>,
+    teams: Query<&Team>,
+    enemies: Query<(), With<Enemy>>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut knockback_events: EventWriter<Knockback>,
+    broadphase: Res<Broadphase>,
+    collision: Res<CollisionGrid>,
+    settings: Res<ServerSettings>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    let friendly_fire = settings.teams.friendly_fire;
+
+    for explosion in explosions.iter() {
+        let Explosion {
+            position,
+            def,
+            source,
+        } = *explosion;
+        debug!(?position, radius = def.radius, ?source, "Explosion");
+
+        let message = ServerMessage::Explosion {
+            position,
+            radius: def.radius,
+        };
+        broadcast_message(&mut server, &mut stats, &message);
+
+        let candidates = broadphase.ships_near(position, Vec2::splat(def.radius * 2.0));
+        for (entity, transform, collider) in ships.iter_many(candidates) {
+            let offset = transform.translation.truncate() - position;
+            let distance = collider.distance_to(transform, position);
+            if distance > def.radius {
+                continue;
+            }
+
+            if def.blocked_by_walls && !collision.line_of_sight(position, position + offset) {
+                continue;
+            }
+
+            // Same rules as projectiles, enemies don't hurt each other and teammates
+            // depend on the friendly fire setting. Owners always take their own blast
+            let target = match source {
+                Some(source) if source == entity => entity,
+                Some(source) if enemies.contains(source) && enemies.contains(entity) => continue,
+                Some(source) => {
+                    let teammates = matches!(
+                        (teams.get(source), teams.get(entity)),
+                        (Ok(owner), Ok(target)) if owner == target
+                    );
+                    match (teammates, friendly_fire) {
+                        (true, FriendlyFire::Off) => continue,
+                        (true, FriendlyFire::Reflect) => source,
+                        _ => entity,
+                    }
+                }
+                None => entity,
+            };
+
+            let falloff = 1.0 - distance / def.radius.max(f32::EPSILON);
+            damage_events.send(DamageEvent {
+                target,
+                amount: def.damage * falloff,
+                source,
+            });
+
+            if target == entity && def.knockback > 0.0 {
+                let direction = match offset.normalize_or_zero() {
+                    Vec2::ZERO => Vec2::Y,
+                    direction => direction,
+                };
+                knockback_events.send(Knockback {
+                    target: entity,
+                    velocity: direction * def.knockback * falloff,
+                });
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_knockback(
+    mut knockback_events: EventReader<Knockback>,
+    mut knockbacks: ResMut<Knockbacks>,
+    mut ships: Query<&mut Transform, (Or<(With<Player>, With<Enemy>)>, Without<Dead>)>,
+    collision: Res<CollisionGrid>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_seconds();
+    let drag = (1.0 - KNOCKBACK_DRAG * delta_seconds).max(0.0);
+
+    for knockback in knockback_events.iter() {
+        *knockbacks.0.entry(knockback.target).or_default() += knockback.velocity;
+    }
+
+    knockbacks.0.retain(|entity, velocity| {
+        let Ok(mut transform) = ships.get_mut(*entity) else {
+            return false;
+        };
+
+        let position = transform.translation.truncate();
+        let moved = collision.move_and_slide(
+            position,
+            Vec2::splat(PLAYER_SIZE),
+            *velocity * delta_seconds,
+        );
+        transform.translation.x = moved.x;
+        transform.translation.y = moved.y;
+
+        *velocity *= drag;
+        velocity.length() > KNOCKBACK_STOP
+    });
+}
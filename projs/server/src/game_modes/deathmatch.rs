@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use blitz_common::{MatchPhase, MatchWinner, Player, Score};
+
+use crate::{config::ServerSettings, networking::resources::ServerLobby};
+
+use super::{game_mode_active, MatchEnded, MatchTimeUp};
+
+pub const DEATHMATCH: &str = "deathmatch";
+
+/// Free-for-all: every kill counts for the shooter, the first to the score limit wins.
+pub struct DeathmatchPlugin;
+impl Plugin for DeathmatchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (score_limit_reached, time_up)
+                .in_set(OnUpdate(MatchPhase::InProgress))
+                .distributive_run_if(game_mode_active(DEATHMATCH)),
+        );
+    }
+}
+
+/// What ranks players in a deathmatch, against the score limit as well as when time runs out.
+fn frags(score: &Score) -> u32 {
+    score.kills
+}
+
+fn score_limit_reached(
+    players: Query<(&Player, &Score)>,
+    lobby: Res<ServerLobby>,
+    settings: Res<ServerSettings>,
+    mut match_ended: EventWriter<MatchEnded>,
+) {
+    let Some(score_limit) = settings.match_rules.score_limit else {
+        return;
+    };
+
+    if let Some((player, _)) = players
+        .iter()
+        .find(|(_, score)| frags(score) >= score_limit)
+    {
+        match_ended.send(MatchEnded {
+            winner: MatchWinner::Player {
+                id: player.id,
+                name: lobby.name(player.id).to_string(),
+            },
+        });
+    }
+}
+
+fn time_up(
+    mut match_time_up: EventReader<MatchTimeUp>,
+    players: Query<(&Player, &Score)>,
+    lobby: Res<ServerLobby>,
+    mut match_ended: EventWriter<MatchEnded>,
+) {
+    if match_time_up.is_empty() {
+        return;
+    }
+    match_time_up.clear();
+
+    let mut ranking: Vec<_> = players.iter().collect();
+    ranking.sort_by_key(|(_, score)| std::cmp::Reverse(frags(score)));
+
+    let winner = match ranking.as_slice() {
+        [(first, first_score), (_, second_score), ..]
+            if frags(first_score) > frags(second_score) =>
+        {
+            MatchWinner::Player {
+                id: first.id,
+                name: lobby.name(first.id).to_string(),
+            }
+        }
+        [(first, _)] => MatchWinner::Player {
+            id: first.id,
+            name: lobby.name(first.id).to_string(),
+        },
+        _ => MatchWinner::Draw,
+    };
+
+    match_ended.send(MatchEnded { winner });
+}
@@ -0,0 +1,278 @@
+use bevy::prelude::*;
+use bevy_renet::renet::{RenetServer, ServerEvent};
+use blitz_common::{Health, MatchPhase, MatchWinner, Player, Score, ServerMessage};
+
+use crate::{
+    config::ServerSettings,
+    health::Dead,
+    networking::{
+        broadcast_message,
+        resources::{NetworkStats, ServerLobby},
+        send_message,
+    },
+    spawning::Spawning,
+    waves::SURVIVAL,
+};
+
+mod deathmatch;
+mod team_deathmatch;
+
+use deathmatch::{DeathmatchPlugin, DEATHMATCH};
+use team_deathmatch::{TeamDeathmatchPlugin, TEAM_DEATHMATCH};
+
+/// Every game mode the server knows about, and whether it is played in teams.
+const GAME_MODES: [(&str, bool); 3] = [
+    (DEATHMATCH, false),
+    (TEAM_DEATHMATCH, true),
+    (SURVIVAL, false),
+];
+
+/// The mode the current match is played in. Mode plugins only run when they are selected and
+/// decide when the match is won. Spawn selection and kill and assist scoring are shared by every
+/// mode, the only say a mode has in them is whether it is played in teams.
+#[derive(Debug, Resource)]
+pub struct ActiveGameMode {
+    pub id: &'static str,
+    pub teams: bool,
+}
+
+/// Time left in the current phase.
+#[derive(Debug, Default, Resource)]
+pub struct MatchClock(pub Option<Timer>);
+
+#[derive(Debug, Default, Resource)]
+pub struct MatchResult(pub Option<MatchWinner>);
+
+/// Sent by the active game mode once its win condition is met.
+#[derive(Debug, Clone)]
+pub struct MatchEnded {
+    pub winner: MatchWinner,
+}
+
+/// Sent when the time limit runs out, the active game mode decides who won.
+#[derive(Debug, Clone, Copy)]
+pub struct MatchTimeUp;
+
+pub fn game_mode_active(id: &'static str) -> impl Fn(Res<ActiveGameMode>) -> bool + Clone {
+    move |mode: Res<ActiveGameMode>| mode.id == id
+}
+
+pub fn team_game_mode(mode: Res<ActiveGameMode>) -> bool {
+    mode.teams
+}
+
+pub struct ServerGameModesPlugin;
+impl Plugin for ServerGameModesPlugin {
+    fn build(&self, app: &mut App) {
+        let game_mode = app
+            .world
+            .resource::<ServerSettings>()
+            .match_rules
+            .game_mode
+            .clone();
+
+        let (id, teams) = GAME_MODES
+            .into_iter()
+            .find(|(id, _)| *id == game_mode)
+            .unwrap_or_else(|| {
+                warn!(%game_mode, "Unknown game mode, falling back to {DEATHMATCH}");
+                GAME_MODES[0]
+            });
+        info!(game_mode = id, teams, "Game mode selected");
+
+        app.add_state::<MatchPhase>();
+        app.insert_resource(ActiveGameMode { id, teams });
+        app.init_resource::<MatchClock>();
+        app.init_resource::<MatchResult>();
+
+        app.add_event::<MatchEnded>();
+        app.add_event::<MatchTimeUp>();
+
+        app.add_plugin(DeathmatchPlugin);
+        app.add_plugin(TeamDeathmatchPlugin);
+
+        app.add_system(enter_warmup.in_schedule(OnEnter(MatchPhase::Warmup)));
+        app.add_system(enter_countdown.in_schedule(OnEnter(MatchPhase::Countdown)));
+        app.add_system(start_match.in_schedule(OnEnter(MatchPhase::InProgress)));
+        app.add_system(enter_post_match.in_schedule(OnEnter(MatchPhase::PostMatch)));
+        app.add_system(change_map.in_schedule(OnEnter(MatchPhase::MapChange)));
+
+        app.add_system(update_warmup.in_set(OnUpdate(MatchPhase::Warmup)));
+        app.add_system(update_countdown.in_set(OnUpdate(MatchPhase::Countdown)));
+        app.add_system(update_match.in_set(OnUpdate(MatchPhase::InProgress)));
+        app.add_system(update_post_match.in_set(OnUpdate(MatchPhase::PostMatch)));
+
+        app.add_systems((
+            broadcast_match_state.run_if(state_changed::<MatchPhase>()),
+            sync_match_state,
+        ));
+    }
+}
+
+fn timer(seconds: f32) -> Option<Timer> {
+    Some(Timer::from_seconds(seconds, TimerMode::Once))
+}
+
+/// Ticks the phase clock, returning true once it runs out.
+fn tick_clock(clock: &mut MatchClock, time: &Time) -> bool {
+    match &mut clock.0 {
+        Some(timer) => timer.tick(time.delta()).just_finished(),
+        None => false,
+    }
+}
+
+fn match_state(phase: MatchPhase, clock: &MatchClock, result: &MatchResult) -> ServerMessage {
+    ServerMessage::MatchState {
+        phase,
+        time_left: clock.0.as_ref().map(|timer| timer.remaining_secs()),
+        winner: result.0.clone(),
+    }
+}
+
+fn enter_warmup(
+    mut clock: ResMut<MatchClock>,
+    mut result: ResMut<MatchResult>,
+    settings: Res<ServerSettings>,
+) {
+    info!("Warmup");
+    clock.0 = timer(settings.match_rules.warmup_time);
+    result.0 = None;
+}
+
+fn update_warmup(
+    mut clock: ResMut<MatchClock>,
+    mut next_phase: ResMut<NextState<MatchPhase>>,
+    lobby: Res<ServerLobby>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+) {
+    // Warmup only counts down once there are enough players to play
+    if lobby.players.len() < settings.match_rules.min_players {
+        clock.0 = timer(settings.match_rules.warmup_time);
+        return;
+    }
+
+    if tick_clock(&mut clock, &time) {
+        next_phase.set(MatchPhase::Countdown);
+    }
+}
+
+fn enter_countdown(mut clock: ResMut<MatchClock>, settings: Res<ServerSettings>) {
+    info!("Match starting");
+    clock.0 = timer(settings.match_rules.countdown_time);
+}
+
+fn update_countdown(
+    mut clock: ResMut<MatchClock>,
+    mut next_phase: ResMut<NextState<MatchPhase>>,
+    time: Res<Time>,
+) {
+    if tick_clock(&mut clock, &time) {
+        next_phase.set(MatchPhase::InProgress);
+    }
+}
+
+/// Everyone starts the match alive, at full health and without any score.
+fn start_match(
+    mut commands: Commands,
+    mut clock: ResMut<MatchClock>,
+    mut players: Query<(Entity, &Player, &mut Health, &mut Score)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    settings: Res<ServerSettings>,
+) {
+    info!("Match started");
+    clock.0 = settings.match_rules.time_limit.and_then(timer);
+
+    for (entity, player, mut health, mut score) in players.iter_mut() {
+        *health = Health::new(settings.player.max_health);
+        *score = Score::default();
+
+        commands.entity(entity).remove::<Dead>().insert(Spawning);
+
+        let messages = [
+            ServerMessage::RespawnPlayer { entity },
+            ServerMessage::PlayerHealth {
+                entity,
+                health: *health,
+            },
+            ServerMessage::PlayerScore {
+                id: player.id,
+                score: *score,
+            },
+        ];
+
+        for message in messages {
+            broadcast_message(&mut server, &mut stats, &message);
+        }
+    }
+}
+
+fn update_match(
+    mut clock: ResMut<MatchClock>,
+    mut result: ResMut<MatchResult>,
+    mut match_ended: EventReader<MatchEnded>,
+    mut match_time_up: EventWriter<MatchTimeUp>,
+    mut next_phase: ResMut<NextState<MatchPhase>>,
+    time: Res<Time>,
+) {
+    if tick_clock(&mut clock, &time) {
+        match_time_up.send(MatchTimeUp);
+    }
+
+    if let Some(ended) = match_ended.iter().next() {
+        result.0 = Some(ended.winner.clone());
+        next_phase.set(MatchPhase::PostMatch);
+    }
+}
+
+fn enter_post_match(
+    mut clock: ResMut<MatchClock>,
+    result: Res<MatchResult>,
+    settings: Res<ServerSettings>,
+) {
+    info!(winner = ?result.0, "Match over");
+    clock.0 = timer(settings.match_rules.post_match_time);
+}
+
+fn update_post_match(
+    mut clock: ResMut<MatchClock>,
+    mut next_phase: ResMut<NextState<MatchPhase>>,
+    time: Res<Time>,
+) {
+    if tick_clock(&mut clock, &time) {
+        next_phase.set(MatchPhase::MapChange);
+    }
+}
+
+fn change_map(mut next_phase: ResMut<NextState<MatchPhase>>) {
+    next_phase.set(MatchPhase::Warmup);
+}
+
+fn broadcast_match_state(
+    phase: Res<State<MatchPhase>>,
+    clock: Res<MatchClock>,
+    result: Res<MatchResult>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    let message = match_state(phase.0, &clock, &result);
+    broadcast_message(&mut server, &mut stats, &message);
+}
+
+/// Newly connected clients need to know where the match is at.
+fn sync_match_state(
+    mut server_events: EventReader<ServerEvent>,
+    phase: Res<State<MatchPhase>>,
+    clock: Res<MatchClock>,
+    result: Res<MatchResult>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for event in server_events.iter() {
+        if let ServerEvent::ClientConnected(id, _) = event {
+            let message = match_state(phase.0, &clock, &result);
+            send_message(&mut server, &mut stats, *id, &message);
+        }
+    }
+}
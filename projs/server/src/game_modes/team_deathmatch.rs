@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use blitz_common::{MatchPhase, MatchWinner, Score, Team};
+
+use crate::config::ServerSettings;
+
+use super::{game_mode_active, MatchEnded, MatchTimeUp};
+
+pub const TEAM_DEATHMATCH: &str = "team_deathmatch";
+
+/// Two teams, the kills of every member count towards the team's score.
+pub struct TeamDeathmatchPlugin;
+impl Plugin for TeamDeathmatchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (score_limit_reached, time_up)
+                .in_set(OnUpdate(MatchPhase::InProgress))
+                .distributive_run_if(game_mode_active(TEAM_DEATHMATCH)),
+        );
+    }
+}
+
+fn team_kills(players: &Query<(&Team, &Score)>) -> [u32; 2] {
+    let mut kills = [0; 2];
+    for (team, score) in players.iter() {
+        kills[*team as usize] += score.kills;
+    }
+    kills
+}
+
+fn score_limit_reached(
+    players: Query<(&Team, &Score)>,
+    settings: Res<ServerSettings>,
+    mut match_ended: EventWriter<MatchEnded>,
+) {
+    let Some(score_limit) = settings.match_rules.score_limit else {
+        return;
+    };
+
+    let kills = team_kills(&players);
+    if let Some(team) = Team::ALL
+        .into_iter()
+        .find(|team| kills[*team as usize] >= score_limit)
+    {
+        match_ended.send(MatchEnded {
+            winner: MatchWinner::Team(team),
+        });
+    }
+}
+
+fn time_up(
+    mut match_time_up: EventReader<MatchTimeUp>,
+    players: Query<(&Team, &Score)>,
+    mut match_ended: EventWriter<MatchEnded>,
+) {
+    if match_time_up.is_empty() {
+        return;
+    }
+    match_time_up.clear();
+
+    let [a, b] = team_kills(&players);
+    let winner = match a.cmp(&b) {
+        std::cmp::Ordering::Greater => MatchWinner::Team(Team::A),
+        std::cmp::Ordering::Less => MatchWinner::Team(Team::B),
+        std::cmp::Ordering::Equal => MatchWinner::Draw,
+    };
+
+    match_ended.send(MatchEnded { winner });
+}
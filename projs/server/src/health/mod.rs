@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use blitz_common::{Health, Player, ServerMessage};
+
+use crate::{
+    abilities::Shield,
+    config::ServerSettings,
+    networking::{broadcast_message, resources::NetworkStats},
+    spawning::Spawning,
+    status::StatusEffects,
+};
+
+/// Damage dealt to an entity with [`Health`], `source` being whoever gets the credit.
+#[derive(Debug, Clone, Copy)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub source: Option<Entity>,
+}
+
+/// Damage that actually got through to a player, after invulnerability, shields and
+/// status effects had their say.
+#[derive(Debug, Clone, Copy)]
+pub struct DamageApplied {
+    pub target: Entity,
+    pub source: Option<Entity>,
+}
+
+/// Sent when a player's health drops to zero.
+#[derive(Debug, Clone, Copy)]
+pub struct PlayerKilled {
+    pub victim: Entity,
+    pub killer: Option<Entity>,
+}
+
+/// A dead player can't move, shoot or be hit until it respawns.
+#[derive(Debug, Component)]
+pub struct Dead {
+    pub respawn: Timer,
+}
+
+#[derive(Debug, Component)]
+pub struct Invulnerable(pub Timer);
+
+pub struct ServerHealthPlugin;
+impl Plugin for ServerHealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>();
+        app.add_event::<DamageApplied>();
+        app.add_event::<PlayerKilled>();
+
+        app.add_systems((apply_damage, respawn_players, tick_invulnerability));
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn apply_damage(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut damage_applied: EventWriter<DamageApplied>,
+    mut player_killed: EventWriter<PlayerKilled>,
+    mut players: Query<
+        (&mut Health, Option<&mut Shield>, Option<&StatusEffects>),
+        (With<Player>, Without<Dead>, Without<Invulnerable>),
+    >,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    settings: Res<ServerSettings>,
+) {
+    for damage in damage_events.iter() {
+        let Ok((mut health, shield, effects)) = players.get_mut(damage.target) else {
+            continue;
+        };
+
+        // Several hits can land in the same frame, only the first kill counts
+        if health.is_dead() {
+            continue;
+        }
+
+        let amount = damage.amount * effects.map_or(1.0, StatusEffects::incoming_damage_multiplier);
+        let amount = match shield {
+            Some(mut shield) => shield.absorb(amount),
+            None => amount,
+        };
+        if amount <= 0.0 {
+            continue;
+        }
+
+        health.current = (health.current - amount).max(0.0);
+        damage_applied.send(DamageApplied {
+            target: damage.target,
+            source: damage.source,
+        });
+
+        let message = ServerMessage::PlayerHealth {
+            entity: damage.target,
+            health: *health,
+        };
+        broadcast_message(&mut server, &mut stats, &message);
+
+        if health.is_dead() {
+            debug!(network_id = ?damage.target, killer = ?damage.source, "Player killed");
+
+            commands.entity(damage.target).insert(Dead {
+                respawn: Timer::from_seconds(settings.player.respawn_delay, TimerMode::Once),
+            });
+
+            player_killed.send(PlayerKilled {
+                victim: damage.target,
+                killer: damage.source,
+            });
+
+            let message = ServerMessage::DespawnPlayer {
+                entity: damage.target,
+            };
+            broadcast_message(&mut server, &mut stats, &message);
+        }
+    }
+}
+
+fn respawn_players(
+    mut commands: Commands,
+    mut dead_players: Query<(Entity, &mut Dead, &mut Health)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+) {
+    for (entity, mut dead, mut health) in dead_players.iter_mut() {
+        dead.respawn.tick(time.delta());
+        if !dead.respawn.finished() {
+            continue;
+        }
+
+        debug!(network_id = ?entity, "Player respawned");
+
+        *health = Health::new(settings.player.max_health);
+
+        commands
+            .entity(entity)
+            .remove::<Dead>()
+            .insert(Spawning)
+            .insert(Invulnerable(Timer::from_seconds(
+                settings.player.respawn_invulnerability,
+                TimerMode::Once,
+            )));
+
+        let messages = [
+            ServerMessage::RespawnPlayer { entity },
+            ServerMessage::PlayerHealth {
+                entity,
+                health: *health,
+            },
+        ];
+
+        for message in messages {
+            broadcast_message(&mut server, &mut stats, &message);
+        }
+    }
+}
+
+fn tick_invulnerability(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Invulnerable)>,
+    time: Res<Time>,
+) {
+    for (entity, mut invulnerable) in query.iter_mut() {
+        invulnerable.0.tick(time.delta());
+        if invulnerable.0.finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
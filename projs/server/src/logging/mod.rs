@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter};
+
+use crate::{config::LogSettings, console::ConsoleCommand};
+
+/// Lets the log filter be swapped while the server is running.
+#[derive(Resource)]
+pub struct LogFilterHandle(reload::Handle<EnvFilter, tracing_subscriber::Registry>);
+
+/// Number of the current server tick, attached to log spans.
+#[derive(Debug, Default, Resource)]
+pub struct ServerTick(pub u64);
+
+pub struct ServerLoggingPlugin;
+impl Plugin for ServerLoggingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ServerTick>();
+
+        app.add_system(advance_tick.in_base_set(CoreSet::First));
+        app.add_system(change_log_filter);
+    }
+}
+
+/// Installs the global subscriber. `RUST_LOG` takes precedence over the configured filter.
+pub fn init_logging(settings: &LogSettings) -> LogFilterHandle {
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&settings.filter))
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(settings.json.then(|| fmt::layer().json().with_current_span(true)))
+        .with((!settings.json).then(fmt::layer))
+        .init();
+
+    LogFilterHandle(handle)
+}
+
+fn advance_tick(mut tick: ResMut<ServerTick>) {
+    tick.0 += 1;
+}
+
+fn change_log_filter(
+    mut console_commands: EventReader<ConsoleCommand>,
+    handle: Res<LogFilterHandle>,
+) {
+    for command in console_commands.iter() {
+        if command.name != "log" {
+            continue;
+        }
+
+        let filter = command.args.join(" ");
+        match EnvFilter::try_new(&filter) {
+            Ok(new_filter) => match handle.0.reload(new_filter) {
+                Ok(()) => info!(%filter, "Log filter changed"),
+                Err(e) => warn!("Failed to change log filter: {e}"),
+            },
+            Err(e) => warn!(%filter, "Invalid log filter: {e}"),
+        }
+    }
+}
@@ -1,30 +1,107 @@
+mod abilities;
+mod bans;
+mod broadphase;
 mod collisions;
+mod config;
+mod console;
+mod enemies;
+mod explosions;
+mod game_modes;
+mod health;
+mod logging;
+mod map;
+mod metrics;
+mod navigation;
 mod networking;
+mod pickups;
 mod players;
 mod projectiles;
+mod query;
+mod scores;
+mod shutdown;
+mod spawning;
+mod status;
+mod teams;
+mod waves;
+mod weapons;
+mod zones;
 
 use bevy::prelude::*;
 
 use blitz_common::panic_on_error_system;
 
 use crate::{
-    collisions::ServerCollisionsPlugin, networking::ServerNetworkPlugin,
-    players::ServerPlayerPlugin, projectiles::ServerProjectilesPlugin,
+    abilities::ServerAbilitiesPlugin,
+    bans::ServerBansPlugin,
+    broadphase::ServerBroadphasePlugin,
+    collisions::ServerCollisionsPlugin,
+    config::ServerSettings,
+    console::ServerConsolePlugin,
+    enemies::ServerEnemiesPlugin,
+    explosions::ServerExplosionsPlugin,
+    game_modes::ServerGameModesPlugin,
+    health::ServerHealthPlugin,
+    logging::{init_logging, ServerLoggingPlugin},
+    map::ServerMapPlugin,
+    metrics::ServerMetricsPlugin,
+    navigation::ServerNavigationPlugin,
+    networking::ServerNetworkPlugin,
+    pickups::ServerPickupsPlugin,
+    players::ServerPlayerPlugin,
+    projectiles::ServerProjectilesPlugin,
+    query::ServerQueryPlugin,
+    scores::ServerScoresPlugin,
+    shutdown::ServerShutdownPlugin,
+    spawning::ServerSpawningPlugin,
+    status::ServerStatusPlugin,
+    teams::ServerTeamsPlugin,
+    waves::ServerWavesPlugin,
+    weapons::ServerWeaponsPlugin,
+    zones::ServerZonesPlugin,
 };
 
 fn main() {
-    println!("Starting Blitz Server...");
+    let settings = ServerSettings::load();
+    let log_filter = init_logging(&settings.log);
+
+    info!("Starting Blitz Server...");
 
     let mut app = App::new();
     app.add_plugins(MinimalPlugins);
 
+    app.insert_resource(settings);
+    app.insert_resource(log_filter);
+
+    app.add_plugin(ServerConsolePlugin);
+    app.add_plugin(ServerLoggingPlugin);
+
+    app.add_plugin(ServerMapPlugin);
+    app.add_plugin(ServerSpawningPlugin);
+    app.add_plugin(ServerNavigationPlugin);
     app.add_plugin(ServerPlayerPlugin);
     app.add_plugin(ServerNetworkPlugin);
+    app.add_plugin(ServerWeaponsPlugin);
+    app.add_plugin(ServerAbilitiesPlugin);
+    app.add_plugin(ServerStatusPlugin);
     app.add_plugin(ServerProjectilesPlugin);
+    app.add_plugin(ServerBroadphasePlugin);
+    app.add_plugin(ServerPickupsPlugin);
+    app.add_plugin(ServerEnemiesPlugin);
     app.add_plugin(ServerCollisionsPlugin);
+    app.add_plugin(ServerWavesPlugin);
+    app.add_plugin(ServerExplosionsPlugin);
+    app.add_plugin(ServerZonesPlugin);
+    app.add_plugin(ServerHealthPlugin);
+    app.add_plugin(ServerScoresPlugin);
+    app.add_plugin(ServerGameModesPlugin);
+    app.add_plugin(ServerTeamsPlugin);
+    app.add_plugin(ServerBansPlugin);
+    app.add_plugin(ServerMetricsPlugin);
+    app.add_plugin(ServerShutdownPlugin);
+    app.add_plugin(ServerQueryPlugin);
 
     app.add_system(panic_on_error_system);
 
-    println!("Blitz Server Running!");
+    info!("Blitz Server Running!");
     app.run();
 }
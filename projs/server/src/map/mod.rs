@@ -0,0 +1,235 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_renet::renet::{RenetServer, ServerEvent};
+use blitz_common::{
+    CollisionGrid, LevelData, MapData, MatchPhase, Player, Projectile, ServerMessage, WORLD_FILE,
+};
+
+use crate::{
+    config::{ServerSettings, ASSETS_DIR},
+    console::ConsoleCommand,
+    networking::{broadcast_message, resources::NetworkStats, send_message},
+    spawning::{SpawnPoints, Spawning},
+};
+
+/// Levels played in turn, advanced every time the match phase reaches `MapChange`.
+#[derive(Debug, Resource)]
+pub struct MapRotation {
+    levels: Vec<String>,
+    current: usize,
+    /// Set from the console, played instead of the next level in the rotation
+    next: Option<String>,
+}
+
+impl MapRotation {
+    pub fn current(&self) -> &str {
+        &self.levels[self.current]
+    }
+
+    pub fn peek_next(&self) -> &str {
+        match &self.next {
+            Some(next) => next,
+            None => &self.levels[(self.current + 1) % self.levels.len()],
+        }
+    }
+
+    fn advance(&mut self) -> String {
+        match self.next.take() {
+            // Jumping to a level in the rotation continues the rotation from there
+            Some(next) => {
+                if let Some(index) = self.levels.iter().position(|level| *level == next) {
+                    self.current = index;
+                }
+                next
+            }
+            None => {
+                self.current = (self.current + 1) % self.levels.len();
+                self.levels[self.current].clone()
+            }
+        }
+    }
+}
+
+/// The level everyone is playing on.
+#[derive(Debug, Resource)]
+pub struct CurrentLevel {
+    pub identifier: String,
+    pub hash: u64,
+}
+
+impl CurrentLevel {
+    fn message(&self) -> ServerMessage {
+        ServerMessage::LoadLevel {
+            identifier: self.identifier.clone(),
+            hash: self.hash,
+        }
+    }
+}
+
+pub struct ServerMapPlugin;
+impl Plugin for ServerMapPlugin {
+    fn build(&self, app: &mut App) {
+        let path = Path::new(ASSETS_DIR).join(WORLD_FILE);
+        let map = MapData::load(&path).unwrap_or_else(|e| {
+            error!("Failed to load map, nothing will collide with walls: {e}");
+            MapData::default()
+        });
+        info!("Loaded {} level(s)", map.levels.len());
+
+        let configured = &app.world.resource::<ServerSettings>().match_rules.map_rotation;
+        let mut levels: Vec<_> = configured
+            .iter()
+            .filter(|identifier| {
+                let known = map.level(identifier).is_some();
+                if !known {
+                    warn!(level = %identifier, "Unknown level in map rotation, skipping it");
+                }
+                known
+            })
+            .cloned()
+            .collect();
+
+        if levels.is_empty() {
+            levels = map.levels.iter().map(|level| level.identifier.clone()).collect();
+        }
+
+        let first = levels.first().and_then(|identifier| map.level(identifier));
+        let (collision, current) = match first {
+            Some(level) => {
+                info!(level = %level.identifier, rotation = ?levels, "Starting map rotation");
+                (level.collision.clone(), current_level(level))
+            }
+            None => (
+                CollisionGrid::default(),
+                CurrentLevel {
+                    identifier: String::new(),
+                    hash: 0,
+                },
+            ),
+        };
+
+        app.insert_resource(MapRotation {
+            levels,
+            current: 0,
+            next: None,
+        });
+        app.insert_resource(current);
+        app.insert_resource(map);
+        app.insert_resource(collision);
+
+        app.add_system(change_level.in_schedule(OnEnter(MatchPhase::MapChange)));
+        app.add_systems((map_commands, sync_level));
+    }
+}
+
+fn current_level(level: &LevelData) -> CurrentLevel {
+    CurrentLevel {
+        identifier: level.identifier.clone(),
+        hash: level.hash,
+    }
+}
+
+/// Swaps in the next level between matches and sends everyone there.
+#[allow(clippy::too_many_arguments)]
+fn change_level(
+    mut commands: Commands,
+    mut rotation: ResMut<MapRotation>,
+    mut current: ResMut<CurrentLevel>,
+    mut collision: ResMut<CollisionGrid>,
+    mut spawn_points: ResMut<SpawnPoints>,
+    players: Query<Entity, With<Player>>,
+    projectiles: Query<Entity, With<Projectile>>,
+    map: Res<MapData>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    if rotation.levels.is_empty() {
+        return;
+    }
+
+    let identifier = rotation.advance();
+    let Some(level) = map.level(&identifier) else {
+        error!(level = %identifier, "Level vanished from the map");
+        return;
+    };
+    info!(level = %identifier, "Changing level");
+
+    *collision = level.collision.clone();
+    *spawn_points = SpawnPoints::from_level(Some(level), &collision);
+    *current = current_level(level);
+
+    // Removing the projectiles also tells the clients about them
+    for entity in projectiles.iter() {
+        commands.entity(entity).despawn();
+    }
+    for entity in players.iter() {
+        commands.entity(entity).insert(Spawning);
+    }
+
+    broadcast_message(&mut server, &mut stats, &current.message());
+}
+
+fn map_commands(
+    mut console_commands: EventReader<ConsoleCommand>,
+    mut rotation: ResMut<MapRotation>,
+    mut next_phase: ResMut<NextState<MatchPhase>>,
+    map: Res<MapData>,
+) {
+    for command in console_commands.iter() {
+        let immediately = match command.name.as_str() {
+            "map" => true,
+            "nextmap" => false,
+            _ => continue,
+        };
+
+        if rotation.levels.is_empty() {
+            warn!("No levels loaded");
+            continue;
+        }
+
+        let Some(identifier) = command.args.first() else {
+            match immediately {
+                true => warn!("Usage: map <level>"),
+                false => info!(
+                    current = rotation.current(),
+                    "Next map: {}",
+                    rotation.peek_next()
+                ),
+            }
+            continue;
+        };
+
+        if map.level(identifier).is_none() {
+            let levels: Vec<_> = map
+                .levels
+                .iter()
+                .map(|level| level.identifier.as_str())
+                .collect();
+            warn!(level = %identifier, "Unknown level, available: {}", levels.join(", "));
+            continue;
+        }
+
+        rotation.next = Some(identifier.clone());
+        match immediately {
+            true => {
+                info!(level = %identifier, "Changing map now");
+                next_phase.set(MatchPhase::MapChange);
+            }
+            false => info!(level = %identifier, "Next map set"),
+        }
+    }
+}
+
+fn sync_level(
+    mut server_events: EventReader<ServerEvent>,
+    current: Res<CurrentLevel>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for event in server_events.iter() {
+        if let ServerEvent::ClientConnected(id, _) = event {
+            send_message(&mut server, &mut stats, *id, &current.message());
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::{
+    fmt::Write as _,
+    io::{Read, Write},
+    net::TcpListener,
+    sync::{Arc, RwLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use blitz_common::{Enemy, Player, Projectile};
+
+use crate::{
+    config::ServerSettings,
+    networking::resources::{ChannelStats, NetworkStats, ServerLobby},
+    pickups::Pickup,
+};
+
+/// Upper bounds of the tick duration histogram buckets, in seconds.
+const TICK_BUCKETS: [f64; 8] = [0.0005, 0.001, 0.002, 0.004, 0.008, 0.016, 0.033, 0.066];
+
+pub struct ServerMetricsPlugin;
+impl Plugin for ServerMetricsPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = app.world.resource::<ServerSettings>().metrics.clone();
+        if !settings.enabled {
+            return;
+        }
+
+        let listener = match TcpListener::bind(&settings.address) {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!(address = %settings.address, "Failed to bind metrics endpoint: {e}");
+                return;
+            }
+        };
+        info!(address = %settings.address, "Serving metrics");
+
+        let snapshot = MetricsSnapshot::default();
+        serve_metrics(listener, snapshot.0.clone());
+
+        app.insert_resource(snapshot);
+        app.insert_resource(TickMetrics::default());
+
+        app.add_system(start_tick.in_base_set(CoreSet::First));
+        app.add_systems((end_tick, render_metrics).chain().in_base_set(CoreSet::Last));
+    }
+}
+
+/// Latest rendered metrics, shared with the HTTP thread.
+#[derive(Debug, Default, Clone, Resource)]
+struct MetricsSnapshot(Arc<RwLock<String>>);
+
+#[derive(Debug, Resource)]
+struct TickMetrics {
+    tick_start: Instant,
+    buckets: [u64; TICK_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+    render_timer: Timer,
+}
+
+impl Default for TickMetrics {
+    fn default() -> Self {
+        Self {
+            tick_start: Instant::now(),
+            buckets: [0; TICK_BUCKETS.len()],
+            count: 0,
+            sum: 0.0,
+            render_timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+        }
+    }
+}
+
+impl TickMetrics {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+
+        for (bucket, bound) in self.buckets.iter_mut().zip(TICK_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+fn serve_metrics(listener: TcpListener, snapshot: Arc<RwLock<String>>) {
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+
+            // Only the request line matters, whatever the path we answer with the metrics
+            let mut request = [0u8; 1024];
+            stream.set_read_timeout(Some(Duration::from_secs(1))).ok();
+            let _ = stream.read(&mut request);
+
+            let body = snapshot.read().map(|body| body.clone()).unwrap_or_default();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).ok();
+        }
+    });
+}
+
+fn start_tick(mut tick_metrics: ResMut<TickMetrics>) {
+    tick_metrics.tick_start = Instant::now();
+}
+
+fn end_tick(mut tick_metrics: ResMut<TickMetrics>) {
+    let duration = tick_metrics.tick_start.elapsed();
+    tick_metrics.observe(duration);
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn render_metrics(
+    mut tick_metrics: ResMut<TickMetrics>,
+    snapshot: Res<MetricsSnapshot>,
+    server: Res<RenetServer>,
+    stats: Res<NetworkStats>,
+    lobby: Res<ServerLobby>,
+    players: Query<(), With<Player>>,
+    projectiles: Query<(), With<Projectile>>,
+    pickups: Query<(), With<Pickup>>,
+    enemies: Query<(), With<Enemy>>,
+    time: Res<Time>,
+) {
+    tick_metrics.render_timer.tick(time.delta());
+    if !tick_metrics.render_timer.just_finished() {
+        return;
+    }
+
+    let mut out = String::new();
+
+    writeln!(out, "# TYPE blitz_connected_players gauge").ok();
+    writeln!(out, "blitz_connected_players {}", lobby.players.len()).ok();
+
+    writeln!(out, "# TYPE blitz_entities gauge").ok();
+    for (kind, count) in [
+        ("player", players.iter().count()),
+        ("projectile", projectiles.iter().count()),
+        ("pickup", pickups.iter().count()),
+        ("enemy", enemies.iter().count()),
+    ] {
+        writeln!(out, "blitz_entities{{type=\"{kind}\"}} {count}").ok();
+    }
+
+    writeln!(out, "# TYPE blitz_tick_duration_seconds histogram").ok();
+    for (bound, count) in TICK_BUCKETS.iter().zip(tick_metrics.buckets) {
+        writeln!(
+            out,
+            "blitz_tick_duration_seconds_bucket{{le=\"{bound}\"}} {count}"
+        )
+        .ok();
+    }
+    writeln!(
+        out,
+        "blitz_tick_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+        tick_metrics.count
+    )
+    .ok();
+    writeln!(out, "blitz_tick_duration_seconds_sum {}", tick_metrics.sum).ok();
+    writeln!(out, "blitz_tick_duration_seconds_count {}", tick_metrics.count).ok();
+
+    let channel_metrics: [(&str, fn(&ChannelStats) -> u64); 2] = [
+        ("bytes", |channel_stats| channel_stats.bytes),
+        ("messages", |channel_stats| channel_stats.messages),
+    ];
+    for (metric, value) in channel_metrics {
+        writeln!(out, "# TYPE blitz_channel_{metric}_total counter").ok();
+        for (direction, channels) in [("sent", &stats.sent), ("received", &stats.received)] {
+            for (channel, channel_stats) in channels {
+                writeln!(
+                    out,
+                    "blitz_channel_{metric}_total{{direction=\"{direction}\",channel=\"{channel}\"}} {}",
+                    value(channel_stats)
+                )
+                .ok();
+            }
+        }
+    }
+
+    writeln!(out, "# TYPE blitz_client_messages_total counter").ok();
+    writeln!(
+        out,
+        "blitz_client_messages_total{{result=\"decoded\"}} {}",
+        stats.decoded
+    )
+    .ok();
+    writeln!(
+        out,
+        "blitz_client_messages_total{{result=\"rejected\"}} {}",
+        stats.rejected
+    )
+    .ok();
+
+    let network_infos: Vec<_> = server
+        .clients_id()
+        .into_iter()
+        .filter_map(|client_id| Some((client_id, server.network_info(client_id)?)))
+        .collect();
+
+    writeln!(out, "# TYPE blitz_client_rtt_seconds gauge").ok();
+    for (client_id, info) in &network_infos {
+        writeln!(
+            out,
+            "blitz_client_rtt_seconds{{client_id=\"{client_id}\"}} {}",
+            info.rtt / 1000.0
+        )
+        .ok();
+    }
+
+    writeln!(out, "# TYPE blitz_client_packet_loss_ratio gauge").ok();
+    for (client_id, info) in &network_infos {
+        writeln!(
+            out,
+            "blitz_client_packet_loss_ratio{{client_id=\"{client_id}\"}} {}",
+            info.packet_loss
+        )
+        .ok();
+    }
+
+    if let Ok(mut body) = snapshot.0.write() {
+        *body = out;
+    }
+}
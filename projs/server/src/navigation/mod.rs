@@ -0,0 +1,440 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    sync::Arc,
+};
+
+use bevy::{math::ivec2, prelude::*};
+use bevy_renet::renet::RenetServer;
+use blitz_common::{CollisionGrid, ServerMessage, PLAYER_SIZE};
+
+use crate::{
+    config::ServerSettings,
+    console::ConsoleCommand,
+    networking::{broadcast_message, resources::NetworkStats},
+};
+
+/// Cost of a straight step, diagonal steps cost `DIAGONAL_COST`, roughly times √2.
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+/// Cells an agent of [`PLAYER_SIZE`] can stand in, row 0 at the bottom like the collision grid.
+#[derive(Debug, Clone, Default)]
+pub struct NavGrid {
+    width: i32,
+    height: i32,
+    cell_size: f32,
+    walkable: Vec<bool>,
+}
+
+impl NavGrid {
+    pub fn from_collision(collision: &CollisionGrid, agent_size: f32) -> Self {
+        let mut walkable = Vec::with_capacity((collision.width * collision.height).max(0) as usize);
+
+        for y in 0..collision.height {
+            for x in 0..collision.width {
+                let center = collision.cell_center(ivec2(x, y));
+                walkable.push(!collision.overlaps(center, Vec2::splat(agent_size)));
+            }
+        }
+
+        Self {
+            width: collision.width,
+            height: collision.height,
+            cell_size: collision.cell_size,
+            walkable,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.walkable.is_empty()
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let inside = cell.x >= 0 && cell.y >= 0 && cell.x < self.width && cell.y < self.height;
+        inside.then(|| (cell.y * self.width + cell.x) as usize)
+    }
+
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.index(cell).is_some_and(|index| self.walkable[index])
+    }
+
+    pub fn cell_at(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn cell_center(&self, cell: IVec2) -> Vec2 {
+        (cell.as_vec2() + 0.5) * self.cell_size
+    }
+
+    /// A* over the 8 neighbours of every cell. Diagonal steps can't cut wall corners.
+    /// Returns the cells from `start` to `goal`, both included. An agent pushed into a cell it
+    /// doesn't fit in first steps out to a walkable neighbour, which comes second.
+    fn search(&self, start: IVec2, goal: IVec2) -> Option<Vec<IVec2>> {
+        let goal_index = self.index(goal).filter(|index| self.walkable[*index])?;
+        let mut start_index = self.index(start)?;
+
+        let stuck = !self.walkable[start_index];
+        if stuck {
+            let exit = NEIGHBOURS
+                .iter()
+                .map(|(offset, _)| start + *offset)
+                .find(|cell| self.is_walkable(*cell))?;
+            start_index = self.index(exit)?;
+        }
+
+        let mut came_from: HashMap<usize, usize> = HashMap::new();
+        let mut cost = vec![u32::MAX; self.walkable.len()];
+        let mut open = BinaryHeap::new();
+
+        cost[start_index] = 0;
+        open.push(Reverse((
+            heuristic(self.cell_of(start_index), goal),
+            start_index,
+        )));
+
+        while let Some(Reverse((estimate, index))) = open.pop() {
+            let cell = self.cell_of(index);
+
+            // A cheaper way to this cell was found after this entry was queued
+            if estimate - heuristic(cell, goal) > cost[index] {
+                continue;
+            }
+
+            if index == goal_index {
+                let mut cells = vec![goal];
+                let mut current = index;
+                while let Some(&previous) = came_from.get(&current) {
+                    cells.push(self.cell_of(previous));
+                    current = previous;
+                }
+                if stuck {
+                    cells.push(start);
+                }
+                cells.reverse();
+                return Some(cells);
+            }
+
+            for (offset, step_cost) in NEIGHBOURS {
+                let next = cell + offset;
+                let Some(next_index) = self.index(next).filter(|index| self.walkable[*index])
+                else {
+                    continue;
+                };
+
+                let diagonal = offset.x != 0 && offset.y != 0;
+                if diagonal
+                    && !(self.is_walkable(cell + ivec2(offset.x, 0))
+                        && self.is_walkable(cell + ivec2(0, offset.y)))
+                {
+                    continue;
+                }
+
+                let next_cost = cost[index] + step_cost;
+                if next_cost < cost[next_index] {
+                    cost[next_index] = next_cost;
+                    came_from.insert(next_index, index);
+                    open.push(Reverse((next_cost + heuristic(next, goal), next_index)));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn cell_of(&self, index: usize) -> IVec2 {
+        ivec2(index as i32 % self.width, index as i32 / self.width)
+    }
+}
+
+const NEIGHBOURS: [(IVec2, u32); 8] = [
+    (IVec2::X, STRAIGHT_COST),
+    (IVec2::NEG_X, STRAIGHT_COST),
+    (IVec2::Y, STRAIGHT_COST),
+    (IVec2::NEG_Y, STRAIGHT_COST),
+    (IVec2::ONE, DIAGONAL_COST),
+    (IVec2::NEG_ONE, DIAGONAL_COST),
+    (IVec2::new(1, -1), DIAGONAL_COST),
+    (IVec2::new(-1, 1), DIAGONAL_COST),
+];
+
+/// Octile distance, exact on an empty grid.
+fn heuristic(from: IVec2, to: IVec2) -> u32 {
+    let delta = (to - from).abs();
+    let (short, long) = (delta.x.min(delta.y) as u32, delta.x.max(delta.y) as u32);
+    DIAGONAL_COST * short + STRAIGHT_COST * (long - short)
+}
+
+/// Answers path queries on the current level for every server side agent.
+#[derive(Debug, Resource)]
+pub struct Navigation {
+    grid: NavGrid,
+    /// Used to check a smoothed segment is wide enough for the agent
+    collision: CollisionGrid,
+    cache: HashMap<(IVec2, IVec2), Arc<[Vec2]>>,
+    cache_size: usize,
+    /// Paths computed since the last time they were streamed to the clients
+    debug_paths: Vec<(Entity, Vec<Vec2>)>,
+    pub debug: bool,
+}
+
+impl Navigation {
+    fn new(cache_size: usize, debug: bool) -> Self {
+        Self {
+            grid: NavGrid::default(),
+            collision: CollisionGrid::default(),
+            cache: HashMap::new(),
+            cache_size,
+            debug_paths: Vec::new(),
+            debug,
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.grid.cell_size
+    }
+
+    /// Waypoints leading `agent` from `from` to `to`, `None` when it can't get there.
+    /// The last waypoint is the center of the cell `to` is in.
+    pub fn find_path(&mut self, agent: Entity, from: Vec2, to: Vec2) -> Option<Arc<[Vec2]>> {
+        if self.grid.is_empty() {
+            return Some(Arc::from([to]));
+        }
+
+        let key = (self.grid.cell_at(from), self.grid.cell_at(to));
+        let path = match self.cache.get(&key) {
+            Some(path) => path.clone(),
+            None => {
+                let cells = self.grid.search(key.0, key.1)?;
+                let path: Arc<[Vec2]> = self.smooth(&cells).into();
+
+                // Levels are small, starting over is cheaper than tracking what to evict
+                if self.cache.len() >= self.cache_size {
+                    self.cache.clear();
+                }
+                self.cache.insert(key, path.clone());
+                path
+            }
+        };
+
+        if self.debug {
+            let points = std::iter::once(from).chain(path.iter().copied()).collect();
+            self.debug_paths.push((agent, points));
+        }
+
+        Some(path)
+    }
+
+    /// Drops every cell center that can be skipped by going straight to a later one.
+    fn smooth(&self, cells: &[IVec2]) -> Vec<Vec2> {
+        let points: Vec<_> = cells.iter().map(|cell| self.grid.cell_center(*cell)).collect();
+        let mut path = Vec::new();
+        let mut anchor = 0;
+
+        while anchor + 1 < points.len() {
+            let next = (anchor + 1..points.len())
+                .rev()
+                .find(|&index| self.is_clear(points[anchor], points[index]))
+                .unwrap_or(anchor + 1);
+
+            path.push(points[next]);
+            anchor = next;
+        }
+
+        if path.is_empty() {
+            path.extend(points.last());
+        }
+        path
+    }
+
+    /// Whether an agent can move in a straight line between two points.
+    fn is_clear(&self, from: Vec2, to: Vec2) -> bool {
+        let step = self.grid.cell_size / 4.0;
+        let samples = (from.distance(to) / step).ceil().max(1.0) as i32;
+        let size = Vec2::splat(PLAYER_SIZE);
+
+        (0..=samples).all(|i| {
+            let point = from.lerp(to, i as f32 / samples as f32);
+            !self.collision.overlaps(point, size)
+        })
+    }
+
+    fn rebuild(&mut self, collision: &CollisionGrid) {
+        self.grid = NavGrid::from_collision(collision, PLAYER_SIZE);
+        self.collision = collision.clone();
+        self.cache.clear();
+    }
+}
+
+pub struct ServerNavigationPlugin;
+impl Plugin for ServerNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = &app.world.resource::<ServerSettings>().navigation;
+        let navigation = Navigation::new(settings.cache_size, settings.debug_paths);
+
+        app.insert_resource(navigation);
+
+        app.add_system(rebuild_navigation.run_if(resource_changed::<CollisionGrid>()));
+        app.add_systems((navigation_commands, stream_debug_paths));
+    }
+}
+
+fn rebuild_navigation(mut navigation: ResMut<Navigation>, collision: Res<CollisionGrid>) {
+    navigation.rebuild(&collision);
+
+    let walkable = navigation.grid.walkable.iter().filter(|walkable| **walkable).count();
+    info!(walkable, cells = navigation.grid.walkable.len(), "Built navigation grid");
+}
+
+fn navigation_commands(
+    mut console_commands: EventReader<ConsoleCommand>,
+    mut navigation: ResMut<Navigation>,
+) {
+    for command in console_commands.iter() {
+        if command.name != "navdebug" {
+            continue;
+        }
+
+        navigation.debug = match command.args.first().map(String::as_str) {
+            Some("on") => true,
+            Some("off") => false,
+            None => !navigation.debug,
+            Some(_) => {
+                warn!("Usage: navdebug [on|off]");
+                continue;
+            }
+        };
+        info!(enabled = navigation.debug, "Navigation debug paths");
+    }
+}
+
+fn stream_debug_paths(
+    mut navigation: ResMut<Navigation>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    if navigation.debug_paths.is_empty() {
+        return;
+    }
+
+    for (entity, points) in std::mem::take(&mut navigation.debug_paths) {
+        let message = ServerMessage::DebugPath { entity, points };
+        broadcast_message(&mut server, &mut stats, &message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cells twice the agent's size, so exactly the solid ones aren't walkable.
+    const CELL_SIZE: f32 = PLAYER_SIZE * 2.0;
+
+    fn collision(width: i32, height: i32, solid: &[(i32, i32)]) -> CollisionGrid {
+        let mut collision = CollisionGrid::new(width, height, CELL_SIZE);
+        for (x, y) in solid {
+            collision.set_solid(ivec2(*x, *y));
+        }
+        collision
+    }
+
+    fn grid(width: i32, height: i32, solid: &[(i32, i32)]) -> NavGrid {
+        NavGrid::from_collision(&collision(width, height, solid), PLAYER_SIZE)
+    }
+
+    fn cost(cells: &[IVec2]) -> u32 {
+        cells
+            .windows(2)
+            .map(|step| heuristic(step[0], step[1]))
+            .sum()
+    }
+
+    #[test]
+    fn heuristic_is_octile_distance() {
+        assert_eq!(heuristic(ivec2(0, 0), ivec2(0, 0)), 0);
+        assert_eq!(heuristic(ivec2(0, 0), ivec2(3, 0)), 3 * STRAIGHT_COST);
+        assert_eq!(heuristic(ivec2(0, 0), ivec2(-2, 2)), 2 * DIAGONAL_COST);
+        assert_eq!(
+            heuristic(ivec2(1, 1), ivec2(4, 2)),
+            DIAGONAL_COST + 2 * STRAIGHT_COST
+        );
+    }
+
+    #[test]
+    fn open_grid_path_is_shortest() {
+        let cells = grid(5, 5, &[]).search(ivec2(0, 0), ivec2(4, 2)).unwrap();
+        assert_eq!(cells.first(), Some(&ivec2(0, 0)));
+        assert_eq!(cells.last(), Some(&ivec2(4, 2)));
+        assert_eq!(cost(&cells), heuristic(ivec2(0, 0), ivec2(4, 2)));
+    }
+
+    #[test]
+    fn path_around_a_wall_is_shortest() {
+        // A wall in the middle column with a gap at the top
+        let grid = grid(5, 5, &[(2, 0), (2, 1), (2, 2), (2, 3)]);
+        let cells = grid.search(ivec2(0, 0), ivec2(4, 0)).unwrap();
+
+        assert!(cells.iter().all(|cell| grid.is_walkable(*cell)));
+        assert!(cells.contains(&ivec2(2, 4)));
+        // Up along the wall, through the gap without clipping its end, and back down
+        let shortest = heuristic(ivec2(0, 0), ivec2(1, 4))
+            + 2 * STRAIGHT_COST
+            + heuristic(ivec2(3, 4), ivec2(4, 0));
+        assert_eq!(cost(&cells), shortest);
+    }
+
+    #[test]
+    fn goal_inside_a_wall_has_no_path() {
+        let grid = grid(4, 4, &[(3, 3)]);
+        assert_eq!(grid.search(ivec2(0, 0), ivec2(3, 3)), None);
+        assert_eq!(grid.search(ivec2(0, 0), ivec2(10, 0)), None);
+    }
+
+    #[test]
+    fn diagonal_does_not_cut_corners() {
+        let grid = grid(2, 2, &[(1, 0)]);
+        let cells = grid.search(ivec2(0, 0), ivec2(1, 1)).unwrap();
+        assert_eq!(cells, [ivec2(0, 0), ivec2(0, 1), ivec2(1, 1)]);
+    }
+
+    #[test]
+    fn unreachable_region_has_no_path() {
+        let grid = grid(5, 3, &[(2, 0), (2, 1), (2, 2)]);
+        assert_eq!(grid.search(ivec2(0, 1), ivec2(4, 1)), None);
+    }
+
+    #[test]
+    fn start_inside_a_wall_steps_out_first() {
+        let grid = grid(4, 3, &[(0, 1)]);
+        let cells = grid.search(ivec2(0, 1), ivec2(3, 1)).unwrap();
+
+        assert_eq!(cells[0], ivec2(0, 1));
+        assert!(cells[1..].iter().all(|cell| grid.is_walkable(*cell)));
+        assert!((cells[1] - cells[0]).abs().max_element() <= 1);
+        assert_eq!(cells.last(), Some(&ivec2(3, 1)));
+    }
+
+    #[test]
+    fn smoothing_goes_straight_on_an_open_grid() {
+        let mut navigation = Navigation::new(16, false);
+        navigation.rebuild(&collision(6, 6, &[]));
+
+        let cells = navigation.grid.search(ivec2(0, 0), ivec2(5, 3)).unwrap();
+        assert!(cells.len() > 2);
+        assert_eq!(
+            navigation.smooth(&cells),
+            [navigation.grid.cell_center(ivec2(5, 3))]
+        );
+    }
+
+    #[test]
+    fn smoothing_keeps_the_corner_of_a_wall() {
+        let mut navigation = Navigation::new(16, false);
+        navigation.rebuild(&collision(5, 5, &[(2, 0), (2, 1), (2, 2), (2, 3)]));
+
+        let cells = navigation.grid.search(ivec2(0, 0), ivec2(4, 0)).unwrap();
+        let path = navigation.smooth(&cells);
+        assert!(path.len() >= 2);
+        assert_eq!(path.last(), Some(&navigation.grid.cell_center(ivec2(4, 0))));
+    }
+}
@@ -1,20 +1,28 @@
 use std::{net::UdpSocket, time::SystemTime};
 
-use bevy::{
-    math::{vec2, vec3},
-    prelude::*,
-};
+use bevy::{math::vec3, prelude::*};
 use bevy_renet::{
     renet::{RenetConnectionConfig, RenetServer, ServerAuthentication, ServerConfig, ServerEvent},
     RenetServerPlugin,
 };
 use blitz_common::{
-    ClientChannel, FromPlayer, NetworkedEntities, Player, PlayerCommand, PlayerInput, Projectile,
-    ServerChannel, ServerMessage, PROTOCOL_ID,
+    ClientChannel, ConnectionData, Enemy, Health, NetworkedEntities, Player, PlayerCommand,
+    PlayerInput, Projectile, Score, ServerChannel, ServerMessage, PROTOCOL_ID,
+};
+
+use crate::{
+    abilities::AbilityCommand,
+    bans::{BanList, PendingRejections},
+    config::ServerSettings,
+    health::Dead,
+    logging::ServerTick,
+    spawning::Spawning,
+    teams::TeamChangeRequest,
+    weapons::WeaponCommand,
 };
 
-mod resources;
-use resources::ServerLobby;
+pub mod resources;
+use resources::{NetworkStats, ServerLobby};
 
 pub fn server_connection_config() -> RenetConnectionConfig {
     RenetConnectionConfig {
@@ -24,12 +32,16 @@ pub fn server_connection_config() -> RenetConnectionConfig {
     }
 }
 
-fn new_renet_server() -> RenetServer {
+fn new_renet_server(max_clients: usize) -> RenetServer {
     let server_addr = "127.0.0.1:5001".parse().unwrap();
     let socket = UdpSocket::bind(server_addr).unwrap();
     let connection_config = server_connection_config();
-    let server_config =
-        ServerConfig::new(64, PROTOCOL_ID, server_addr, ServerAuthentication::Unsecure);
+    let server_config = ServerConfig::new(
+        max_clients,
+        PROTOCOL_ID,
+        server_addr,
+        ServerAuthentication::Unsecure,
+    );
     let current_time = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .unwrap();
@@ -42,73 +54,228 @@ impl Plugin for ServerNetworkPlugin {
         app.add_plugin(RenetServerPlugin::default());
 
         app.insert_resource(ServerLobby::default());
-        app.insert_resource(new_renet_server());
+        app.insert_resource(NetworkStats::default());
+        let max_clients = app.world.resource::<ServerSettings>().capacity.max_clients();
+
+        app.insert_resource(new_renet_server(max_clients));
 
         app.add_systems((server_update, server_sync_entities));
     }
 }
 
-fn server_update(
+/// Sends a reliable server message to a single client.
+pub fn send_message(
+    server: &mut RenetServer,
+    stats: &mut NetworkStats,
+    client_id: u64,
+    message: &ServerMessage,
+) {
+    let message = bincode::serialize(message).expect("Failed to Serialize message!");
+    stats.record_sent(ServerChannel::ServerMessages, message.len(), 1);
+    server.send_message(client_id, ServerChannel::ServerMessages, message);
+}
+
+/// Sends a reliable server message to every connected client.
+pub fn broadcast_message(
+    server: &mut RenetServer,
+    stats: &mut NetworkStats,
+    message: &ServerMessage,
+) {
+    let message = bincode::serialize(message).expect("Failed to Serialize message!");
+    stats.record_sent(
+        ServerChannel::ServerMessages,
+        message.len(),
+        server.clients_id().len(),
+    );
+    server.broadcast_message(ServerChannel::ServerMessages, message);
+}
+
+/// Where a newly connected client ends up.
+enum Slot {
+    Player,
+    Spectator,
+    Queued,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn server_update(
     mut commands: Commands,
     mut server_events: EventReader<ServerEvent>,
     mut lobby: ResMut<ServerLobby>,
     mut server: ResMut<RenetServer>,
-    players: Query<(Entity, &Player, &Transform)>,
+    mut stats: ResMut<NetworkStats>,
+    ban_list: Res<BanList>,
+    mut pending_rejections: ResMut<PendingRejections>,
+    settings: Res<ServerSettings>,
+    mut team_change_requests: EventWriter<TeamChangeRequest>,
+    mut weapon_commands: EventWriter<WeaponCommand>,
+    mut ability_commands: EventWriter<AbilityCommand>,
+    tick: Res<ServerTick>,
+    players: Query<(Entity, &Player, &Health, &Score, Option<&Dead>)>,
 ) {
+    let _tick_span = info_span!("tick", tick = tick.0).entered();
+    let capacity = &settings.capacity;
+
     for event in server_events.iter() {
         match event {
-            ServerEvent::ClientConnected(id, _) => {
-                println!("Client {id} Connected!!");
-
-                for (entity, player, _) in players.iter() {
-                    let message = bincode::serialize(&ServerMessage::PlayerCreate {
-                        id: player.id,
-                        entity,
-                    })
-                    .expect("Failed to Serialize message!");
-                    server.send_message(*id, ServerChannel::ServerMessages, message);
+            ServerEvent::ClientConnected(id, user_data) => {
+                let _client_span = info_span!("client", client_id = id).entered();
+
+                let connection_data = ConnectionData::from_user_data(user_data)
+                    .unwrap_or_else(|| ConnectionData::new("", false));
+                let ConnectionData {
+                    name,
+                    spectator,
+                    admin_token,
+                } = connection_data;
+                let addr = server.client_addr(*id).map(|addr| addr.ip());
+
+                if let Some(ban) = ban_list.find(&name, addr) {
+                    info!(%name, reason = %ban.reason, "Client rejected");
+
+                    let message = ServerMessage::ConnectionRejected {
+                        reason: format!("Banned: {}", ban.reason),
+                        expires: ban.expires,
+                    };
+                    send_message(&mut server, &mut stats, *id, &message);
+
+                    pending_rejections.push(*id);
+                    continue;
+                }
+
+                let is_admin = capacity.is_admin(admin_token.as_deref());
+                let player_slots = match is_admin {
+                    true => capacity.max_players + capacity.reserved_admin_slots,
+                    false => capacity.max_players,
+                };
+
+                let slot = if spectator {
+                    (lobby.spectators.len() < capacity.spectator_slots).then_some(Slot::Spectator)
+                } else if lobby.players.len() < player_slots {
+                    Some(Slot::Player)
+                } else if lobby.queue.len() < capacity.max_queue_length {
+                    Some(Slot::Queued)
+                } else {
+                    None
+                };
+
+                let Some(slot) = slot else {
+                    info!(%name, "Client rejected, server is full");
+
+                    let message = ServerMessage::ConnectionRejected {
+                        reason: "Server is full".to_string(),
+                        expires: None,
+                    };
+                    send_message(&mut server, &mut stats, *id, &message);
+
+                    pending_rejections.push(*id);
+                    continue;
+                };
+
+                info!(%name, is_admin, "Client connected");
+
+                lobby.names.insert(*id, name);
+                if is_admin {
+                    lobby.admins.insert(*id);
                 }
 
-                let player_entity = commands
-                    .spawn(PbrBundle {
-                        transform: Transform {
-                            translation: vec3(0.0, 0.0, 0.0),
-                            scale: vec3(0.5, 0.5, 1.0),
-                            ..Default::default()
+                // Everyone gets the current state of the world, even when not playing yet
+                for (entity, player, health, score, dead) in players.iter() {
+                    let mut messages = vec![
+                        ServerMessage::PlayerCreate {
+                            id: player.id,
+                            name: lobby.name(player.id).to_string(),
+                            entity,
+                        },
+                        ServerMessage::PlayerHealth {
+                            entity,
+                            health: *health,
                         },
-                        ..Default::default()
-                    })
-                    .insert(PlayerInput::default())
-                    .insert(Player { id: *id })
-                    .id();
-
-                lobby.players.insert(*id, player_entity);
-
-                let message = bincode::serialize(&ServerMessage::PlayerCreate {
-                    id: *id,
-                    entity: player_entity,
-                })
-                .expect("Failed to Serialize message!");
-                server.broadcast_message(ServerChannel::ServerMessages, message);
+                        ServerMessage::PlayerScore {
+                            id: player.id,
+                            score: *score,
+                        },
+                    ];
+                    // Waiting to respawn, the ship shows up again with `RespawnPlayer`
+                    if dead.is_some() {
+                        messages.push(ServerMessage::DespawnPlayer { entity });
+                    }
+
+                    for message in messages {
+                        send_message(&mut server, &mut stats, *id, &message);
+                    }
+                }
+
+                match slot {
+                    Slot::Player => {
+                        spawn_player(
+                            &mut commands,
+                            &mut lobby,
+                            &mut server,
+                            &mut stats,
+                            &settings,
+                            *id,
+                        );
+                    }
+                    Slot::Spectator => {
+                        info!("Client spectating");
+                        lobby.spectators.insert(*id);
+                    }
+                    Slot::Queued => {
+                        info!(position = lobby.queue.len() + 1, "Client queued");
+                        lobby.queue.push_back(*id);
+                        send_queue_positions(&lobby, &mut server, &mut stats);
+                    }
+                }
             }
             ServerEvent::ClientDisconnected(id) => {
-                println!("Client {id} Disconnected!!");
+                let _client_span = info_span!("client", client_id = id).entered();
+                info!("Client disconnected");
+
+                lobby.names.remove(id);
+                lobby.admins.remove(id);
+                lobby.spectators.remove(id);
+                lobby.queue.retain(|queued_id| queued_id != id);
 
                 if let Some(player_entity) = lobby.players.remove(id) {
                     commands.entity(player_entity).despawn();
+
+                    let message = ServerMessage::PlayerDisconnected { id: *id };
+                    broadcast_message(&mut server, &mut stats, &message);
                 }
 
-                let message = bincode::serialize(&ServerMessage::PlayerDisconnected { id: *id })
-                    .expect("Failed to Serialize message!");
-                server.broadcast_message(ServerChannel::ServerMessages, message);
+                while lobby.players.len() < capacity.max_players {
+                    let Some(queued_id) = lobby.queue.pop_front() else {
+                        break;
+                    };
+
+                    info!(client_id = queued_id, "Promoted client from the queue");
+                    spawn_player(
+                        &mut commands,
+                        &mut lobby,
+                        &mut server,
+                        &mut stats,
+                        &settings,
+                        queued_id,
+                    );
+                }
+                send_queue_positions(&lobby, &mut server, &mut stats);
             }
         }
     }
 
     for client_id in server.clients_id().into_iter() {
+        let _client_span = info_span!("client", client_id).entered();
+
         while let Some(message) = server.receive_message(client_id, ClientChannel::Input) {
-            let player_input: PlayerInput =
-                bincode::deserialize(&message).expect("Failed to Deserialize message!");
+            stats.record_received(ClientChannel::Input, message.len());
+
+            let Ok(player_input) = bincode::deserialize::<PlayerInput>(&message) else {
+                warn!("Rejected malformed input");
+                stats.rejected += 1;
+                continue;
+            };
+            stats.decoded += 1;
 
             if let Some(player_entity) = lobby.players.get(&client_id) {
                 commands.entity(*player_entity).insert(player_input);
@@ -116,44 +283,42 @@ fn server_update(
         }
 
         while let Some(message) = server.receive_message(client_id, ClientChannel::Command) {
-            let command: PlayerCommand =
-                bincode::deserialize(&message).expect("Failed to Deserialize message!");
+            stats.record_received(ClientChannel::Command, message.len());
 
-            match command {
-                PlayerCommand::BasicAttack => {
-                    println!("Received basic attack from client {}", client_id);
+            let Ok(command) = bincode::deserialize::<PlayerCommand>(&message) else {
+                warn!("Rejected malformed command");
+                stats.rejected += 1;
+                continue;
+            };
+            stats.decoded += 1;
 
+            match command {
+                PlayerCommand::BasicAttack
+                | PlayerCommand::SelectWeapon(_)
+                | PlayerCommand::NextWeapon
+                | PlayerCommand::PreviousWeapon
+                | PlayerCommand::Reload => {
                     if let Some(player_entity) = lobby.players.get(&client_id) {
-                        if let Ok((_, _, player_transform)) = players.get(*player_entity) {
-                            let projectile_entity = commands
-                                .spawn(SpriteBundle {
-                                    transform: Transform {
-                                        translation: player_transform.translation,
-                                        scale: vec3(0.5, 0.5, 1.0),
-                                        rotation: player_transform.rotation,
-                                    },
-                                    ..Default::default()
-                                })
-                                .insert(Projectile {
-                                    duration: Timer::from_seconds(1.5, TimerMode::Once),
-                                })
-                                .insert(FromPlayer {
-                                    entity: *player_entity,
-                                })
-                                .id();
-
-                            let message = ServerMessage::SpawnProjectile {
-                                entity: projectile_entity,
-                                transform: vec2(
-                                    player_transform.translation.x,
-                                    player_transform.translation.y,
-                                ),
-                                rotation: player_transform.rotation,
-                            };
-                            let message =
-                                bincode::serialize(&message).expect("Failed to Serialize message!");
-                            server.broadcast_message(ServerChannel::ServerMessages, message);
-                        }
+                        weapon_commands.send(WeaponCommand {
+                            entity: *player_entity,
+                            command,
+                        });
+                    }
+                }
+                PlayerCommand::ActivateAbility(_) | PlayerCommand::ReleaseAbility(_) => {
+                    if let Some(player_entity) = lobby.players.get(&client_id) {
+                        ability_commands.send(AbilityCommand {
+                            entity: *player_entity,
+                            command,
+                        });
+                    }
+                }
+                PlayerCommand::ChangeTeam(team) => {
+                    if let Some(player_entity) = lobby.players.get(&client_id) {
+                        team_change_requests.send(TeamChangeRequest {
+                            entity: *player_entity,
+                            team,
+                        });
                     }
                 }
             }
@@ -161,10 +326,58 @@ fn server_update(
     }
 }
 
+fn spawn_player(
+    commands: &mut Commands,
+    lobby: &mut ServerLobby,
+    server: &mut RenetServer,
+    stats: &mut NetworkStats,
+    settings: &ServerSettings,
+    client_id: u64,
+) -> Entity {
+    let player_entity = commands
+        .spawn(PbrBundle {
+            transform: Transform {
+                translation: vec3(0.0, 0.0, 0.0),
+                scale: vec3(0.5, 0.5, 1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(PlayerInput::default())
+        .insert(Player { id: client_id })
+        .insert(Health::new(settings.player.max_health))
+        .insert(Score::default())
+        .insert(Spawning)
+        .id();
+
+    lobby.players.insert(client_id, player_entity);
+    debug!(network_id = ?player_entity, "Spawned player");
+
+    let message = ServerMessage::PlayerCreate {
+        id: client_id,
+        name: lobby.name(client_id).to_string(),
+        entity: player_entity,
+    };
+    broadcast_message(server, stats, &message);
+
+    player_entity
+}
+
+fn send_queue_positions(lobby: &ServerLobby, server: &mut RenetServer, stats: &mut NetworkStats) {
+    for (index, client_id) in lobby.queue.iter().enumerate() {
+        let message = ServerMessage::JoinQueued {
+            position: index + 1,
+            length: lobby.queue.len(),
+        };
+        send_message(server, stats, *client_id, &message);
+    }
+}
+
 #[allow(clippy::type_complexity)]
 fn server_sync_entities(
     mut server: ResMut<RenetServer>,
-    query: Query<(Entity, &Transform), Or<(With<Player>, With<Projectile>)>>,
+    mut stats: ResMut<NetworkStats>,
+    query: Query<(Entity, &Transform), Or<(With<Player>, With<Projectile>, With<Enemy>)>>,
 ) {
     let mut networked_entities = NetworkedEntities::default();
 
@@ -175,5 +388,10 @@ fn server_sync_entities(
 
     let sync_message =
         bincode::serialize(&networked_entities).expect("Failed to Serialize message!");
+    stats.record_sent(
+        ServerChannel::NetworkedEntities,
+        sync_message.len(),
+        server.clients_id().len(),
+    );
     server.broadcast_message(ServerChannel::NetworkedEntities, sync_message);
 }
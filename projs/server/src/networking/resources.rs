@@ -1,8 +1,49 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use bevy::prelude::{Entity, Resource};
+use blitz_common::{ClientChannel, ServerChannel};
 
 #[derive(Debug, Default, Resource)]
 pub struct ServerLobby {
     pub players: HashMap<u64, Entity>,
+    pub names: HashMap<u64, String>,
+    pub admins: HashSet<u64>,
+    pub spectators: HashSet<u64>,
+    /// Clients waiting for a player slot, first in line at the front
+    pub queue: VecDeque<u64>,
+}
+
+impl ServerLobby {
+    pub fn name(&self, client_id: u64) -> &str {
+        self.names.get(&client_id).map_or("", String::as_str)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ChannelStats {
+    pub messages: u64,
+    pub bytes: u64,
+}
+
+/// Running totals of the traffic going through each channel.
+#[derive(Debug, Default, Resource)]
+pub struct NetworkStats {
+    pub sent: HashMap<&'static str, ChannelStats>,
+    pub received: HashMap<&'static str, ChannelStats>,
+    pub decoded: u64,
+    pub rejected: u64,
+}
+
+impl NetworkStats {
+    pub fn record_sent(&mut self, channel: ServerChannel, bytes: usize, recipients: usize) {
+        let stats = self.sent.entry(channel.name()).or_default();
+        stats.messages += recipients as u64;
+        stats.bytes += (bytes * recipients) as u64;
+    }
+
+    pub fn record_received(&mut self, channel: ClientChannel, bytes: usize) {
+        let stats = self.received.entry(channel.name()).or_default();
+        stats.messages += 1;
+        stats.bytes += bytes as u64;
+    }
 }
@@ -0,0 +1,256 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_renet::renet::{RenetServer, ServerEvent};
+use blitz_common::{
+    Collider, Health, MapData, PickupDef, PickupDefs, PickupEffect, Player, ServerMessage,
+    PICKUPS_FILE, PICKUP_ENTITY,
+};
+
+use crate::{
+    broadphase::{update_broadphase, Broadphase},
+    config::ASSETS_DIR,
+    health::Dead,
+    map::CurrentLevel,
+    networking::{broadcast_message, resources::NetworkStats, send_message},
+    spawning::Spawning,
+    status::ApplyStatus,
+    weapons::GiveWeapon,
+};
+
+/// Spot on the map handing out a pickup, comes back `respawn` after being collected.
+#[derive(Debug, Component)]
+pub struct Pickup {
+    pub id: String,
+    respawn: Timer,
+    available: bool,
+}
+
+pub struct ServerPickupsPlugin;
+impl Plugin for ServerPickupsPlugin {
+    fn build(&self, app: &mut App) {
+        let path = Path::new(ASSETS_DIR).join(PICKUPS_FILE);
+        let defs = PickupDefs::load(&path).unwrap_or_else(|e| {
+            error!("Failed to load pickups, maps will have none: {e}");
+            PickupDefs::default()
+        });
+        info!("Loaded {} pickup(s)", defs.pickups.len());
+
+        app.insert_resource(defs);
+
+        app.add_system(spawn_pickups.run_if(resource_changed::<CurrentLevel>()));
+        app.add_systems((
+            respawn_pickups,
+            collect_pickups.after(update_broadphase),
+            sync_pickups,
+        ));
+    }
+}
+
+/// Replaces the pickups of the previous level with the ones placed on the current one.
+fn spawn_pickups(
+    mut commands: Commands,
+    pickups: Query<Entity, With<Pickup>>,
+    current: Res<CurrentLevel>,
+    map: Res<MapData>,
+    defs: Res<PickupDefs>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for entity in pickups.iter() {
+        commands.entity(entity).despawn();
+
+        let message = ServerMessage::DespawnPickup { entity };
+        broadcast_message(&mut server, &mut stats, &message);
+    }
+
+    let Some(level) = map.level(&current.identifier) else {
+        return;
+    };
+
+    for spawner in level.entities(PICKUP_ENTITY) {
+        let Some(id) = spawner.field_str("pickup") else {
+            warn!(level = %level.identifier, "Pickup without a pickup field");
+            continue;
+        };
+
+        let Some(def) = defs.get(id) else {
+            warn!(level = %level.identifier, pickup = %id, "Unknown pickup on the map");
+            continue;
+        };
+
+        let entity = commands
+            .spawn(Transform::from_translation(spawner.position.extend(0.0)))
+            .insert(Pickup {
+                id: def.id.clone(),
+                respawn: Timer::from_seconds(def.respawn_time, TimerMode::Once),
+                available: true,
+            })
+            .insert(def.collider)
+            .id();
+        debug!(network_id = ?entity, pickup = %def.id, "Spawned pickup");
+
+        let message = ServerMessage::PickupSpawned {
+            entity,
+            pickup: def.id.clone(),
+            position: spawner.position,
+        };
+        broadcast_message(&mut server, &mut stats, &message);
+    }
+}
+
+fn respawn_pickups(
+    mut pickups: Query<(Entity, &Transform, &mut Pickup)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    time: Res<Time>,
+) {
+    for (entity, transform, mut pickup) in pickups.iter_mut() {
+        if pickup.available {
+            continue;
+        }
+
+        pickup.respawn.tick(time.delta());
+        if !pickup.respawn.finished() {
+            continue;
+        }
+
+        pickup.available = true;
+
+        let message = ServerMessage::PickupSpawned {
+            entity,
+            pickup: pickup.id.clone(),
+            position: transform.translation.truncate(),
+        };
+        broadcast_message(&mut server, &mut stats, &message);
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn collect_pickups(
+    mut pickups: Query<(Entity, &Transform, &Collider, &mut Pickup)>,
+    mut players: Query<
+        (Entity, &Transform, &Collider, &mut Health),
+        (With<Player>, Without<Dead>, Without<Spawning>),
+    >,
+    mut give_weapon: EventWriter<GiveWeapon>,
+    mut apply_status: EventWriter<ApplyStatus>,
+    defs: Res<PickupDefs>,
+    broadphase: Res<Broadphase>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for (pickup_entity, pickup_transform, pickup_collider, mut pickup) in pickups.iter_mut() {
+        if !pickup.available {
+            continue;
+        }
+
+        let Some(def) = defs.get(&pickup.id) else {
+            continue;
+        };
+
+        let position = pickup_transform.translation.truncate();
+        let candidates = broadphase.ships_near(position, pickup_collider.bounds(pickup_transform));
+        let mut nearby = players.iter_many_mut(candidates);
+
+        while let Some((player_entity, player_transform, player_collider, mut health)) =
+            nearby.fetch_next()
+        {
+            let touching =
+                pickup_collider.intersects(pickup_transform, player_collider, player_transform);
+
+            if !touching
+                || !apply_pickup(
+                    def,
+                    player_entity,
+                    &mut health,
+                    &mut give_weapon,
+                    &mut apply_status,
+                    &mut server,
+                    &mut stats,
+                )
+            {
+                continue;
+            }
+
+            debug!(
+                network_id = ?pickup_entity,
+                pickup = %def.id,
+                player = ?player_entity,
+                "Pickup collected"
+            );
+
+            pickup.available = false;
+            pickup.respawn.reset();
+
+            let message = ServerMessage::PickupCollected {
+                entity: pickup_entity,
+                player: player_entity,
+            };
+            broadcast_message(&mut server, &mut stats, &message);
+            break;
+        }
+    }
+}
+
+/// Returns false when the pickup would do nothing for the player, leaving it for someone else.
+fn apply_pickup(
+    def: &PickupDef,
+    entity: Entity,
+    health: &mut Health,
+    give_weapon: &mut EventWriter<GiveWeapon>,
+    apply_status: &mut EventWriter<ApplyStatus>,
+    server: &mut RenetServer,
+    stats: &mut NetworkStats,
+) -> bool {
+    match &def.effect {
+        PickupEffect::Health { amount } => {
+            if health.current >= health.max {
+                return false;
+            }
+
+            health.current = (health.current + amount).min(health.max);
+
+            let message = ServerMessage::PlayerHealth {
+                entity,
+                health: *health,
+            };
+            broadcast_message(server, stats, &message);
+        }
+        PickupEffect::Weapon { weapon } => give_weapon.send(GiveWeapon {
+            entity,
+            weapon: weapon.clone(),
+        }),
+        PickupEffect::Status { status } => apply_status.send(ApplyStatus {
+            target: entity,
+            status: status.clone(),
+            source: None,
+        }),
+    }
+
+    true
+}
+
+fn sync_pickups(
+    mut server_events: EventReader<ServerEvent>,
+    pickups: Query<(Entity, &Transform, &Pickup)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for event in server_events.iter() {
+        if let ServerEvent::ClientConnected(id, _) = event {
+            for (entity, transform, pickup) in pickups.iter() {
+                if !pickup.available {
+                    continue;
+                }
+
+                let message = ServerMessage::PickupSpawned {
+                    entity,
+                    pickup: pickup.id.clone(),
+                    position: transform.translation.truncate(),
+                };
+                send_message(&mut server, &mut stats, *id, &message);
+            }
+        }
+    }
+}
@@ -1,17 +1,55 @@
-use std::f32::consts::FRAC_PI_2;
+use std::{f32::consts::FRAC_PI_2, path::Path};
 
 use bevy::{math::vec2, prelude::*};
-use blitz_common::{PlayerInput, PLAYER_MOVE_SPEED};
+use blitz_common::{CollisionGrid, Player, PlayerInput, ShipDef, Velocity, PLAYER_SIZE, SHIP_FILE};
+
+use crate::{
+    abilities::Dashing, broadphase::update_broadphase, config::ASSETS_DIR, health::Dead,
+    status::StatusEffects,
+};
 
 pub struct ServerPlayerPlugin;
 impl Plugin for ServerPlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(move_players);
+        let path = Path::new(ASSETS_DIR).join(SHIP_FILE);
+        let ship = ShipDef::load(&path).unwrap_or_else(|e| {
+            error!("Failed to load the ship, falling back to the default collider: {e}");
+            ShipDef::default()
+        });
+
+        app.insert_resource(ship);
+
+        app.add_systems((add_ships, move_players.before(update_broadphase)));
     }
 }
 
-fn move_players(mut query: Query<(&mut Transform, &PlayerInput)>, time: Res<Time>) {
-    for (mut transform, input) in query.iter_mut() {
+fn add_ships(mut commands: Commands, players: Query<Entity, Added<Player>>, ship: Res<ShipDef>) {
+    for entity in players.iter() {
+        commands
+            .entity(entity)
+            .insert((ship.collider, Velocity::default()));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn move_players(
+    mut query: Query<
+        (
+            &mut Transform,
+            &mut Velocity,
+            &PlayerInput,
+            Option<&StatusEffects>,
+            Option<&Dashing>,
+        ),
+        Without<Dead>,
+    >,
+    ship: Res<ShipDef>,
+    collision: Res<CollisionGrid>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_seconds();
+
+    for (mut transform, mut velocity, input, effects, dashing) in query.iter_mut() {
         let player_pos = vec2(transform.translation.x, transform.translation.y);
 
         let mut angle = (input.mouse - player_pos).angle_between(Vec2::X) + FRAC_PI_2;
@@ -21,9 +59,24 @@ fn move_players(mut query: Query<(&mut Transform, &PlayerInput)>, time: Res<Time
         }
 
         let x = (input.right as i8 - input.left as i8) as f32;
-        let y = (input.down as i8 - input.up as i8) as f32;
-        transform.translation.x += x * PLAYER_MOVE_SPEED * time.delta().as_secs_f32();
-        transform.translation.y -= y * PLAYER_MOVE_SPEED * time.delta().as_secs_f32();
+        let y = (input.up as i8 - input.down as i8) as f32;
+        let boost = effects.map_or(1.0, StatusEffects::speed_multiplier);
+        let forward = (transform.rotation * Vec3::Y).truncate();
+        velocity.0 = match dashing {
+            Some(dashing) => dashing.0,
+            None => ship
+                .movement
+                .step(velocity.0, vec2(x, y), forward, boost, delta_seconds),
+        };
+        let delta = velocity.0 * delta_seconds;
+
+        let position = collision.move_and_slide(player_pos, Vec2::splat(PLAYER_SIZE), delta);
+        // What the ship actually covered, sliding along a wall is slower than pushing into it
+        if delta_seconds > 0.0 {
+            velocity.0 = (position - player_pos) / delta_seconds;
+        }
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
         transform.rotation = Quat::from_rotation_z(-angle);
     }
 }
@@ -1,8 +1,11 @@
-use std::f32::consts::{FRAC_PI_2, PI};
-
 use bevy::prelude::*;
 use bevy_renet::renet::RenetServer;
-use blitz_common::{Projectile, ServerChannel, ServerMessage, PLAYER_MOVE_SPEED};
+use blitz_common::{FromPlayer, Projectile, ServerMessage, Velocity};
+
+use crate::{
+    explosions::Explosion,
+    networking::{broadcast_message, resources::NetworkStats},
+};
 
 pub struct ServerProjectilesPlugin;
 impl Plugin for ServerProjectilesPlugin {
@@ -11,42 +14,47 @@ impl Plugin for ServerProjectilesPlugin {
     }
 }
 
-fn move_projectiles(mut query: Query<(&mut Transform, &Projectile)>, time: Res<Time>) {
-    for (mut transform, _) in query.iter_mut() {
-        let (rotation, mut angle) = transform.rotation.to_axis_angle();
-
-        angle = -angle + FRAC_PI_2;
-
-        if rotation.z.is_sign_positive() {
-            angle = -angle + PI;
-        }
+pub fn move_projectiles(
+    mut query: Query<(&mut Transform, &Velocity), With<Projectile>>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_seconds();
 
-        transform.translation.x += PLAYER_MOVE_SPEED * time.delta().as_secs_f32() * angle.cos();
-        transform.translation.y += PLAYER_MOVE_SPEED * time.delta().as_secs_f32() * angle.sin();
+    for (mut transform, velocity) in query.iter_mut() {
+        transform.translation += (velocity.0 * delta_seconds).extend(0.0);
     }
 }
 
 fn update_projectiles(
     mut commands: Commands,
-    mut projectiles: Query<(Entity, &mut Projectile)>,
+    mut projectiles: Query<(Entity, &Transform, &FromPlayer, &mut Projectile)>,
+    mut explosions: EventWriter<Explosion>,
     time: Res<Time>,
 ) {
-    for (entity, mut projectile) in projectiles.iter_mut() {
+    for (entity, transform, from_player, mut projectile) in projectiles.iter_mut() {
         projectile.duration.tick(time.delta());
         if projectile.duration.finished() {
             commands.entity(entity).despawn();
+
+            if let Some(def) = projectile.explosion.take() {
+                explosions.send(Explosion {
+                    position: transform.translation.truncate(),
+                    def,
+                    source: Some(from_player.entity),
+                });
+            }
         }
     }
 }
 
 fn projectile_on_removal(
     mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
     mut removed_projectiles: RemovedComponents<Projectile>,
 ) {
     for entity in &mut removed_projectiles {
         let message = ServerMessage::DespawnProjectile { entity };
-        let message = bincode::serialize(&message).unwrap();
 
-        server.broadcast_message(ServerChannel::ServerMessages, message);
+        broadcast_message(&mut server, &mut stats, &message);
     }
 }
@@ -0,0 +1,95 @@
+use std::net::UdpSocket;
+
+use bevy::prelude::*;
+use blitz_common::{ServerStatus, QUERY_REQUEST};
+
+use crate::{config::ServerSettings, console::ConsoleCommand, networking::resources::ServerLobby};
+
+/// Answers status queries on a separate UDP port, so server browsers don't need a connection.
+pub struct ServerQueryPlugin;
+impl Plugin for ServerQueryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(status_commands);
+
+        let settings = app.world.resource::<ServerSettings>().query.clone();
+        if !settings.enabled {
+            return;
+        }
+
+        let socket = match UdpSocket::bind(&settings.address) {
+            Ok(socket) => socket,
+            Err(e) => {
+                error!(address = %settings.address, "Failed to bind query socket: {e}");
+                return;
+            }
+        };
+        socket
+            .set_nonblocking(true)
+            .expect("Failed to make query socket non-blocking!");
+        info!(address = %settings.address, "Answering status queries");
+
+        app.insert_resource(QuerySocket(socket));
+        app.add_system(answer_queries);
+    }
+}
+
+#[derive(Resource)]
+struct QuerySocket(UdpSocket);
+
+pub fn server_status(lobby: &ServerLobby, settings: &ServerSettings) -> ServerStatus {
+    ServerStatus {
+        players: lobby.players.len(),
+        max_players: settings.capacity.max_players,
+        spectators: lobby.spectators.len(),
+        max_spectators: settings.capacity.spectator_slots,
+        queued: lobby.queue.len(),
+        max_queued: settings.capacity.max_queue_length,
+    }
+}
+
+fn answer_queries(socket: Res<QuerySocket>, lobby: Res<ServerLobby>, settings: Res<ServerSettings>) {
+    let mut buffer = [0u8; 64];
+
+    while let Ok((len, addr)) = socket.0.recv_from(&mut buffer) {
+        if !buffer[..len].starts_with(QUERY_REQUEST) {
+            continue;
+        }
+
+        let status = server_status(&lobby, &settings);
+        let response = bincode::serialize(&status).expect("Failed to Serialize message!");
+        socket.0.send_to(&response, addr).ok();
+    }
+}
+
+fn status_commands(
+    mut console_commands: EventReader<ConsoleCommand>,
+    lobby: Res<ServerLobby>,
+    settings: Res<ServerSettings>,
+) {
+    for command in console_commands.iter() {
+        match command.name.as_str() {
+            "status" => {
+                let status = server_status(&lobby, &settings);
+                info!(
+                    "Players {}/{}, spectators {}/{}, queued {}/{}",
+                    status.players,
+                    status.max_players,
+                    status.spectators,
+                    status.max_spectators,
+                    status.queued,
+                    status.max_queued
+                );
+            }
+            "queue" => {
+                if lobby.queue.is_empty() {
+                    info!("Queue is empty");
+                }
+
+                for (index, client_id) in lobby.queue.iter().enumerate() {
+                    info!("{}. {} ({client_id})", index + 1, lobby.name(*client_id));
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use blitz_common::{Player, Score, ServerMessage, Team};
+
+use crate::{
+    health::{apply_damage, DamageApplied, PlayerKilled},
+    networking::{broadcast_message, resources::NetworkStats},
+    shutdown::ShutdownStarted,
+};
+
+/// Damage older than this doesn't count towards an assist.
+const ASSIST_WINDOW: f32 = 10.0;
+
+/// Who recently damaged a player, used to hand out assists. Hits that didn't get through,
+/// e.g. on a spawn protected player, don't count.
+#[derive(Debug, Default, Component)]
+pub struct DamageHistory(Vec<(Entity, f32)>);
+
+pub struct ServerScoresPlugin;
+impl Plugin for ServerScoresPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PingTimer(Timer::from_seconds(2.0, TimerMode::Repeating)));
+
+        app.add_systems(
+            (record_damage, credit_kills)
+                .chain()
+                .after(apply_damage),
+        );
+        app.add_systems((broadcast_pings, log_final_scores));
+    }
+}
+
+#[derive(Debug, Resource)]
+struct PingTimer(Timer);
+
+fn record_damage(
+    mut commands: Commands,
+    mut damage_applied: EventReader<DamageApplied>,
+    mut histories: Query<&mut DamageHistory>,
+    players: Query<(), With<Player>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+
+    for damage in damage_applied.iter() {
+        // Enemies can be removed as soon as they die, only players keep a history
+        if !players.contains(damage.target) {
+            continue;
+        }
+
+        let Some(source) = damage.source.filter(|source| *source != damage.target) else {
+            continue;
+        };
+
+        match histories.get_mut(damage.target) {
+            Ok(mut history) => {
+                history.0.retain(|(attacker, _)| *attacker != source);
+                history.0.push((source, now));
+            }
+            Err(_) => {
+                if let Some(mut target) = commands.get_entity(damage.target) {
+                    target.insert(DamageHistory(vec![(source, now)]));
+                }
+            }
+        }
+    }
+}
+
+fn credit_kills(
+    mut player_killed: EventReader<PlayerKilled>,
+    mut players: Query<(&Player, &mut Score)>,
+    mut histories: Query<&mut DamageHistory>,
+    teams: Query<&Team>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let teammates = |a: Entity, b: Entity| {
+        matches!((teams.get(a), teams.get(b)), (Ok(a), Ok(b)) if a == b)
+    };
+
+    for kill in player_killed.iter() {
+        let mut changed = vec![kill.victim];
+
+        if let Ok((_, mut score)) = players.get_mut(kill.victim) {
+            score.deaths += 1;
+            score.streak = 0;
+        }
+
+        // Suicides, team kills and map kills don't get anyone a point
+        let killer = kill
+            .killer
+            .filter(|killer| *killer != kill.victim && !teammates(*killer, kill.victim));
+
+        if let Some(killer) = killer {
+            if let Ok((_, mut score)) = players.get_mut(killer) {
+                score.kills += 1;
+                score.streak += 1;
+                score.best_streak = score.best_streak.max(score.streak);
+                changed.push(killer);
+            }
+        }
+
+        if let Ok(mut history) = histories.get_mut(kill.victim) {
+            for (attacker, _) in history
+                .0
+                .drain(..)
+                .filter(|(attacker, at)| {
+                    Some(*attacker) != killer
+                        && !teammates(*attacker, kill.victim)
+                        && now - at <= ASSIST_WINDOW
+                })
+            {
+                if let Ok((_, mut score)) = players.get_mut(attacker) {
+                    score.assists += 1;
+                    changed.push(attacker);
+                }
+            }
+        }
+
+        for entity in changed {
+            if let Ok((player, score)) = players.get(entity) {
+                let message = ServerMessage::PlayerScore {
+                    id: player.id,
+                    score: *score,
+                };
+                broadcast_message(&mut server, &mut stats, &message);
+            }
+        }
+    }
+}
+
+fn broadcast_pings(
+    mut timer: ResMut<PingTimer>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    time: Res<Time>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    let pings = server
+        .clients_id()
+        .into_iter()
+        .filter_map(|client_id| Some((client_id, server.network_info(client_id)?.rtt)))
+        .collect();
+
+    broadcast_message(&mut server, &mut stats, &ServerMessage::PlayerPings { pings });
+}
+
+fn log_final_scores(
+    mut shutdown_started: EventReader<ShutdownStarted>,
+    players: Query<(&Player, &Score)>,
+) {
+    if shutdown_started.is_empty() {
+        return;
+    }
+    shutdown_started.clear();
+
+    let mut scores: Vec<_> = players.iter().collect();
+    scores.sort_by_key(|(_, score)| -score.points());
+
+    for (player, score) in scores {
+        info!(
+            client_id = player.id,
+            points = score.points(),
+            kills = score.kills,
+            deaths = score.deaths,
+            assists = score.assists,
+            best_streak = score.best_streak,
+            "Final score"
+        );
+    }
+}
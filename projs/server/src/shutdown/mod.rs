@@ -0,0 +1,130 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use bevy::{app::AppExit, prelude::*};
+use bevy_renet::renet::RenetServer;
+use blitz_common::ServerMessage;
+
+use crate::{
+    console::ConsoleCommand,
+    networking::{
+        broadcast_message,
+        resources::{NetworkStats, ServerLobby},
+    },
+};
+
+/// Time given to the shutdown message to reach the clients before they are dropped.
+const SHUTDOWN_GRACE: f32 = 1.0;
+
+/// Sent once when the server starts shutting down, so final state can be flushed.
+#[derive(Debug, Clone, Copy)]
+pub struct ShutdownStarted;
+
+#[derive(Debug, Default, Resource)]
+struct ShutdownSignal(Arc<AtomicBool>);
+
+#[derive(Debug, Resource)]
+enum ShutdownState {
+    Running,
+    Notifying(Timer),
+    Disconnected,
+}
+
+pub struct ServerShutdownPlugin;
+impl Plugin for ServerShutdownPlugin {
+    fn build(&self, app: &mut App) {
+        let signal = ShutdownSignal::default();
+
+        let interrupted = signal.0.clone();
+        if let Err(e) = ctrlc::set_handler(move || interrupted.store(true, Ordering::SeqCst)) {
+            warn!("Failed to install signal handler: {e}");
+        }
+
+        app.insert_resource(signal);
+        app.insert_resource(ShutdownState::Running);
+        app.add_event::<ShutdownStarted>();
+
+        app.add_systems((start_shutdown, finish_shutdown).chain());
+    }
+}
+
+fn start_shutdown(
+    signal: Res<ShutdownSignal>,
+    mut console_commands: EventReader<ConsoleCommand>,
+    mut state: ResMut<ShutdownState>,
+    mut shutdown_started: EventWriter<ShutdownStarted>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    lobby: Res<ServerLobby>,
+) {
+    let mut request = None;
+
+    for command in console_commands.iter() {
+        match command.name.as_str() {
+            // shutdown [reason]
+            "shutdown" => request = Some((command.args.join(" "), None)),
+            // restart <seconds> [reason]
+            "restart" => match command.args.first().map(|secs| secs.parse::<f32>()) {
+                Some(Ok(secs)) => request = Some((command.args[1..].join(" "), Some(secs))),
+                _ => warn!("Usage: restart <seconds> [reason]"),
+            },
+            _ => {}
+        }
+    }
+
+    if signal.0.load(Ordering::SeqCst) {
+        request.get_or_insert((String::new(), None));
+    }
+
+    let (ShutdownState::Running, Some((mut reason, reconnect_after))) = (&*state, request) else {
+        return;
+    };
+
+    if reason.is_empty() {
+        reason = "Server is shutting down".to_string();
+    }
+
+    info!(
+        %reason,
+        ?reconnect_after,
+        players = lobby.players.len(),
+        decoded = stats.decoded,
+        rejected = stats.rejected,
+        "Shutting down"
+    );
+
+    let message = ServerMessage::ServerShutdown {
+        reason,
+        reconnect_after,
+    };
+    broadcast_message(&mut server, &mut stats, &message);
+
+    shutdown_started.send(ShutdownStarted);
+    *state = ShutdownState::Notifying(Timer::from_seconds(SHUTDOWN_GRACE, TimerMode::Once));
+}
+
+fn finish_shutdown(
+    mut state: ResMut<ShutdownState>,
+    mut server: ResMut<RenetServer>,
+    mut exit: EventWriter<AppExit>,
+    time: Res<Time>,
+) {
+    match &mut *state {
+        ShutdownState::Running => {}
+        ShutdownState::Notifying(timer) => {
+            timer.tick(time.delta());
+
+            if timer.finished() {
+                server.disconnect_clients();
+                *state = ShutdownState::Disconnected;
+            }
+        }
+        // Give renet one more frame to send out the disconnect packets
+        ShutdownState::Disconnected => {
+            info!("Blitz Server Stopped!");
+            exit.send(AppExit);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use bevy::prelude::*;
+use blitz_common::{CollisionGrid, LevelData, MapData, Player, Team, Velocity};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{
+    config::{ServerSettings, SpawnSelection},
+    health::Dead,
+    map::CurrentLevel,
+};
+
+/// Identifier of the LDtk entity marking where players spawn.
+pub const SPAWN_POINT_ENTITY: &str = "PlayerSpawn";
+
+#[derive(Debug, Clone, Copy)]
+pub struct SpawnPoint {
+    pub position: Vec3,
+    /// Only players of this team spawn here, `None` for everyone
+    pub team: Option<Team>,
+}
+
+#[derive(Debug, Resource)]
+pub struct SpawnPoints(pub Vec<SpawnPoint>);
+
+impl SpawnPoints {
+    /// Spawn points authored in the level, or the free spot closest to its center when there are none.
+    pub fn from_level(level: Option<&LevelData>, collision: &CollisionGrid) -> Self {
+        let mut points: Vec<_> = level
+            .into_iter()
+            .flat_map(|level| level.entities(SPAWN_POINT_ENTITY))
+            .map(|entity| {
+                let team = entity.field_str("team").and_then(|team| {
+                    team.parse::<Team>()
+                        .map_err(|e| warn!(position = %entity.position, "Ignoring spawn team: {e}"))
+                        .ok()
+                });
+
+                SpawnPoint {
+                    position: entity.position.extend(0.0),
+                    team,
+                }
+            })
+            .collect();
+
+        if points.is_empty() {
+            warn!("Level has no {SPAWN_POINT_ENTITY} entities, everyone spawns near its center");
+            let position = collision
+                .nearest_free(collision.size() / 2.0)
+                .unwrap_or(Vec2::ZERO);
+
+            points.push(SpawnPoint {
+                position: position.extend(0.0),
+                team: None,
+            });
+        }
+
+        Self(points)
+    }
+
+    /// Points a player of `team` may spawn at. Team points are preferred over shared ones,
+    /// players without a team can use all of them.
+    fn candidates(&self, team: Option<Team>) -> Vec<Vec2> {
+        let positions = |filter: &dyn Fn(&SpawnPoint) -> bool| -> Vec<Vec2> {
+            self.0
+                .iter()
+                .filter(|point| filter(point))
+                .map(|point| point.position.truncate())
+                .collect()
+        };
+
+        let Some(team) = team else {
+            return positions(&|_| true);
+        };
+
+        let team_points = positions(&|point| point.team == Some(team));
+        if !team_points.is_empty() {
+            return team_points;
+        }
+
+        let shared_points = positions(&|point| point.team.is_none());
+        if !shared_points.is_empty() {
+            return shared_points;
+        }
+
+        positions(&|_| true)
+    }
+}
+
+/// Inserted on a player that has to be moved to a spawn point.
+#[derive(Debug, Component)]
+pub struct Spawning;
+
+#[derive(Debug, Resource)]
+struct SpawnRng(StdRng);
+
+pub struct ServerSpawningPlugin;
+impl Plugin for ServerSpawningPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = app.world.resource::<ServerSettings>().spawns.clone();
+        let map = app.world.resource::<MapData>();
+        let current = app.world.resource::<CurrentLevel>();
+        let collision = app.world.resource::<CollisionGrid>();
+
+        let spawn_points = SpawnPoints::from_level(map.level(&current.identifier), collision);
+        info!(
+            selection = ?settings.selection,
+            "Loaded {} spawn point(s)",
+            spawn_points.0.len()
+        );
+
+        let rng = match settings.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        app.insert_resource(spawn_points);
+        app.insert_resource(SpawnRng(rng));
+
+        // Runs after the commands of `Update` are applied so players respawned this tick are placed
+        app.add_system(place_spawning_players.in_base_set(CoreSet::PostUpdate));
+    }
+}
+
+fn pick_spawn(
+    candidates: &[Vec2],
+    enemies: &[Vec2],
+    selection: SpawnSelection,
+    collision: &CollisionGrid,
+    rng: &mut StdRng,
+) -> Vec2 {
+    let farthest = || {
+        candidates.iter().copied().max_by(|a, b| {
+            let closest = |point: Vec2| {
+                enemies
+                    .iter()
+                    .map(|enemy| enemy.distance(point))
+                    .fold(f32::INFINITY, f32::min)
+            };
+            closest(*a).total_cmp(&closest(*b))
+        })
+    };
+
+    let picked = match selection {
+        // Without anyone around every point is as good as the others
+        _ if enemies.is_empty() => candidates.choose(rng).copied(),
+        SpawnSelection::Random => candidates.choose(rng).copied(),
+        SpawnSelection::FarthestFromEnemies => farthest(),
+        SpawnSelection::AvoidLineOfSight => {
+            let hidden: Vec<_> = candidates
+                .iter()
+                .copied()
+                .filter(|point| {
+                    enemies
+                        .iter()
+                        .all(|enemy| !collision.line_of_sight(*enemy, *point))
+                })
+                .collect();
+
+            hidden.choose(rng).copied().or_else(farthest)
+        }
+    };
+
+    picked.unwrap_or(Vec2::ZERO)
+}
+
+#[allow(clippy::type_complexity)]
+fn place_spawning_players(
+    mut commands: Commands,
+    mut spawning: Query<
+        (Entity, &mut Transform, Option<&mut Velocity>, Option<&Team>),
+        With<Spawning>,
+    >,
+    others: Query<(&Transform, Option<&Team>), (With<Player>, Without<Dead>, Without<Spawning>)>,
+    spawn_points: Res<SpawnPoints>,
+    collision: Res<CollisionGrid>,
+    settings: Res<ServerSettings>,
+    mut rng: ResMut<SpawnRng>,
+) {
+    // Players placed this tick count as enemies so a batch doesn't stack on one point
+    let mut placed: Vec<(Vec2, Option<Team>)> = Vec::new();
+
+    for (entity, mut transform, velocity, team) in spawning.iter_mut() {
+        let team = team.copied();
+        let is_enemy = |other: Option<Team>| team.is_none() || other != team;
+
+        let enemies: Vec<_> = others
+            .iter()
+            .map(|(transform, other)| (transform.translation.truncate(), other.copied()))
+            .chain(placed.iter().copied())
+            .filter(|(_, other)| is_enemy(*other))
+            .map(|(position, _)| position)
+            .collect();
+
+        let position = pick_spawn(
+            &spawn_points.candidates(team),
+            &enemies,
+            settings.spawns.selection,
+            &collision,
+            &mut rng.0,
+        );
+        debug!(network_id = ?entity, %position, "Placed player at spawn");
+
+        transform.translation = position.extend(transform.translation.z);
+        placed.push((position, team));
+
+        // Don't carry the momentum from before dying into the new life
+        if let Some(mut velocity) = velocity {
+            velocity.0 = Vec2::ZERO;
+        }
+
+        commands.entity(entity).remove::<Spawning>();
+    }
+}
@@ -0,0 +1,251 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use blitz_common::{
+    Player, ServerMessage, StatusDef, StatusDefs, StatusModifier, StatusStack, STATUS_EFFECTS_FILE,
+};
+
+use crate::{
+    config::ASSETS_DIR,
+    health::{apply_damage, DamageEvent, Dead, PlayerKilled},
+    networking::{broadcast_message, resources::NetworkStats},
+};
+
+/// Seconds between two hits of a damage over time effect, dealing every frame would
+/// flood the clients with health updates.
+const DAMAGE_TICK: f32 = 0.5;
+
+/// Applies the status effect with the id `status` to `target`, `source` getting the credit
+/// for any damage it deals. Ignored by anything that isn't a living player.
+#[derive(Debug, Clone)]
+pub struct ApplyStatus {
+    pub target: Entity,
+    pub status: String,
+    pub source: Option<Entity>,
+}
+
+#[derive(Debug)]
+struct ActiveStatus {
+    id: String,
+    modifier: StatusModifier,
+    timer: Timer,
+    damage_tick: Timer,
+    source: Option<Entity>,
+}
+
+/// Status effects a player carries that haven't run out yet, one entry per stack.
+#[derive(Debug, Default, Component)]
+pub struct StatusEffects(Vec<ActiveStatus>);
+
+impl StatusEffects {
+    /// Adds a stack, or starts the oldest one over once there are as many as allowed.
+    fn add(&mut self, def: &StatusDef, source: Option<Entity>) {
+        let mut stacks: Vec<_> = self
+            .0
+            .iter_mut()
+            .filter(|active| active.id == def.id)
+            .collect();
+
+        if stacks.len() >= def.max_stacks.max(1) as usize {
+            stacks.sort_by(|a, b| {
+                a.timer
+                    .remaining_secs()
+                    .total_cmp(&b.timer.remaining_secs())
+            });
+            let oldest = &mut stacks[0];
+            oldest.timer.reset();
+            oldest.source = source;
+            return;
+        }
+
+        self.0.push(ActiveStatus {
+            id: def.id.clone(),
+            modifier: def.modifier,
+            timer: Timer::from_seconds(def.duration, TimerMode::Once),
+            damage_tick: Timer::from_seconds(DAMAGE_TICK, TimerMode::Repeating),
+            source,
+        });
+    }
+
+    fn multiplier(&self, pick: impl Fn(&StatusModifier) -> Option<f32>) -> f32 {
+        self.0
+            .iter()
+            .filter_map(|active| pick(&active.modifier))
+            .product()
+    }
+
+    pub fn speed_multiplier(&self) -> f32 {
+        self.multiplier(|modifier| match modifier {
+            StatusModifier::Speed { multiplier } => Some(*multiplier),
+            _ => None,
+        })
+    }
+
+    pub fn damage_multiplier(&self) -> f32 {
+        self.multiplier(|modifier| match modifier {
+            StatusModifier::Damage { multiplier } => Some(*multiplier),
+            _ => None,
+        })
+    }
+
+    pub fn fire_rate_multiplier(&self) -> f32 {
+        self.multiplier(|modifier| match modifier {
+            StatusModifier::FireRate { multiplier } => Some(*multiplier),
+            _ => None,
+        })
+    }
+
+    pub fn incoming_damage_multiplier(&self) -> f32 {
+        self.multiplier(|modifier| match modifier {
+            StatusModifier::IncomingDamage { multiplier } => Some(*multiplier),
+            _ => None,
+        })
+    }
+
+    /// Stacks of the same effect merged the way the clients show them.
+    fn stacks(&self) -> Vec<StatusStack> {
+        let mut stacks: Vec<StatusStack> = Vec::new();
+        for active in &self.0 {
+            let remaining = active.timer.remaining_secs();
+            match stacks.iter_mut().find(|stack| stack.id == active.id) {
+                Some(stack) => {
+                    stack.stacks += 1;
+                    stack.remaining = stack.remaining.max(remaining);
+                }
+                None => stacks.push(StatusStack {
+                    id: active.id.clone(),
+                    stacks: 1,
+                    remaining,
+                }),
+            }
+        }
+        stacks
+    }
+}
+
+pub struct ServerStatusPlugin;
+impl Plugin for ServerStatusPlugin {
+    fn build(&self, app: &mut App) {
+        let path = Path::new(ASSETS_DIR).join(STATUS_EFFECTS_FILE);
+        let defs = StatusDefs::load(&path).unwrap_or_else(|e| {
+            error!("Failed to load status effects, none will be applied: {e}");
+            StatusDefs::default()
+        });
+        info!("Loaded {} status effect(s)", defs.effects.len());
+
+        app.insert_resource(defs);
+        app.add_event::<ApplyStatus>();
+
+        app.add_systems(
+            (
+                add_status_effects,
+                apply_status_effects,
+                tick_status_effects,
+                clear_status_effects,
+            )
+                .chain()
+                .before(apply_damage),
+        );
+    }
+}
+
+fn send_status(
+    server: &mut RenetServer,
+    stats: &mut NetworkStats,
+    entity: Entity,
+    effects: &StatusEffects,
+) {
+    let message = ServerMessage::PlayerStatus {
+        entity,
+        effects: effects.stacks(),
+    };
+    broadcast_message(server, stats, &message);
+}
+
+fn add_status_effects(mut commands: Commands, new_players: Query<Entity, Added<Player>>) {
+    for entity in new_players.iter() {
+        commands.entity(entity).insert(StatusEffects::default());
+    }
+}
+
+fn apply_status_effects(
+    mut apply_status: EventReader<ApplyStatus>,
+    mut players: Query<&mut StatusEffects, Without<Dead>>,
+    defs: Res<StatusDefs>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for request in apply_status.iter() {
+        let Ok(mut effects) = players.get_mut(request.target) else {
+            continue;
+        };
+
+        let Some(def) = defs.get(&request.status) else {
+            warn!(status = %request.status, "Unknown status effect");
+            continue;
+        };
+
+        debug!(network_id = ?request.target, status = %def.id, "Applied status effect");
+        effects.add(def, request.source);
+        send_status(&mut server, &mut stats, request.target, &effects);
+    }
+}
+
+fn tick_status_effects(
+    mut players: Query<(Entity, &mut StatusEffects), Without<Dead>>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    time: Res<Time>,
+) {
+    for (entity, mut effects) in players.iter_mut() {
+        if effects.0.is_empty() {
+            continue;
+        }
+
+        for active in effects.0.iter_mut() {
+            active.timer.tick(time.delta());
+
+            let StatusModifier::DamageOverTime { per_second } = active.modifier else {
+                continue;
+            };
+
+            let ticks = active
+                .damage_tick
+                .tick(time.delta())
+                .times_finished_this_tick();
+            if ticks > 0 {
+                damage_events.send(DamageEvent {
+                    target: entity,
+                    amount: per_second * DAMAGE_TICK * ticks as f32,
+                    source: active.source,
+                });
+            }
+        }
+
+        let count = effects.0.len();
+        effects.0.retain(|active| !active.timer.finished());
+        if effects.0.len() != count {
+            send_status(&mut server, &mut stats, entity, &effects);
+        }
+    }
+}
+
+fn clear_status_effects(
+    mut player_killed: EventReader<PlayerKilled>,
+    mut players: Query<&mut StatusEffects>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for killed in player_killed.iter() {
+        let Ok(mut effects) = players.get_mut(killed.victim) else {
+            continue;
+        };
+
+        if !effects.0.is_empty() {
+            effects.0.clear();
+            send_status(&mut server, &mut stats, killed.victim, &effects);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_renet::renet::{RenetServer, ServerEvent};
+use blitz_common::{Player, ServerMessage, Team};
+
+use crate::{
+    config::ServerSettings,
+    game_modes::team_game_mode,
+    health::Dead,
+    networking::{broadcast_message, resources::NetworkStats, send_message, server_update},
+    spawning::Spawning,
+};
+
+/// How often team sizes are compared when auto-balance is on.
+const BALANCE_INTERVAL: f32 = 5.0;
+
+/// Sent when a player asks to switch to another team.
+#[derive(Debug, Clone, Copy)]
+pub struct TeamChangeRequest {
+    pub entity: Entity,
+    pub team: Team,
+}
+
+pub struct ServerTeamsPlugin;
+impl Plugin for ServerTeamsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TeamChangeRequest>();
+        app.insert_resource(BalanceTimer(Timer::from_seconds(
+            BALANCE_INTERVAL,
+            TimerMode::Repeating,
+        )));
+
+        app.add_systems(
+            (assign_teams, change_teams, auto_balance, sync_teams)
+                .after(server_update)
+                .distributive_run_if(team_game_mode),
+        );
+    }
+}
+
+#[derive(Debug, Resource)]
+struct BalanceTimer(Timer);
+
+fn team_sizes<'a>(teams: impl Iterator<Item = &'a Team>) -> [usize; 2] {
+    let mut sizes = [0; 2];
+    for team in teams {
+        sizes[*team as usize] += 1;
+    }
+    sizes
+}
+
+fn smallest_team(sizes: [usize; 2]) -> Team {
+    if sizes[Team::B as usize] < sizes[Team::A as usize] {
+        Team::B
+    } else {
+        Team::A
+    }
+}
+
+/// Whether a player may leave `current` for `requested` with the teams at `sizes`. With
+/// auto-balance on, switching must not make the teams more uneven than they already are.
+fn switch_allowed(
+    mut sizes: [usize; 2],
+    current: Team,
+    requested: Team,
+    auto_balance: bool,
+) -> bool {
+    sizes[current as usize] -= 1;
+    !auto_balance || sizes[requested as usize] <= sizes[current as usize]
+}
+
+/// Puts `entity` on `team`. Players switching while alive stay where they are, they are
+/// placed at their new team's spawn points the next time they respawn.
+fn join_team(
+    commands: &mut Commands,
+    server: &mut RenetServer,
+    stats: &mut NetworkStats,
+    entity: Entity,
+    player: &Player,
+    team: Team,
+) {
+    info!(client_id = player.id, %team, "Player joined team");
+
+    commands.entity(entity).insert(team);
+
+    let message = ServerMessage::PlayerTeam { id: player.id, team };
+    broadcast_message(server, stats, &message);
+}
+
+#[allow(clippy::type_complexity)]
+fn assign_teams(
+    mut commands: Commands,
+    new_players: Query<(Entity, &Player), (Added<Player>, Without<Team>)>,
+    teams: Query<&Team>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    let mut sizes = team_sizes(teams.iter());
+
+    for (entity, player) in new_players.iter() {
+        let team = smallest_team(sizes);
+        sizes[team as usize] += 1;
+
+        // Placed again now that the team decides which spawn points they may use
+        commands.entity(entity).insert(Spawning);
+        join_team(&mut commands, &mut server, &mut stats, entity, player, team);
+    }
+}
+
+fn change_teams(
+    mut commands: Commands,
+    mut requests: EventReader<TeamChangeRequest>,
+    players: Query<(&Player, &Team)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    settings: Res<ServerSettings>,
+) {
+    // Team inserts are only applied at the end of the frame, so switches accepted this frame
+    // are tracked here
+    let mut sizes = team_sizes(players.iter().map(|(_, team)| team));
+    let mut switched = HashMap::new();
+
+    for request in requests.iter() {
+        let Ok((player, team)) = players.get(request.entity) else {
+            continue;
+        };
+        let current = switched.get(&request.entity).copied().unwrap_or(*team);
+        if current == request.team {
+            continue;
+        }
+
+        if !switch_allowed(sizes, current, request.team, settings.teams.auto_balance) {
+            debug!(client_id = player.id, team = %request.team, "Team change refused");
+            continue;
+        }
+
+        sizes[current as usize] -= 1;
+        sizes[request.team as usize] += 1;
+        switched.insert(request.entity, request.team);
+
+        join_team(
+            &mut commands,
+            &mut server,
+            &mut stats,
+            request.entity,
+            player,
+            request.team,
+        );
+    }
+}
+
+fn auto_balance(
+    mut commands: Commands,
+    mut timer: ResMut<BalanceTimer>,
+    players: Query<(Entity, &Player, &Team, Option<&Dead>)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+) {
+    if !settings.teams.auto_balance {
+        return;
+    }
+
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    let sizes = team_sizes(players.iter().map(|(_, _, team, _)| team));
+    let smaller = smallest_team(sizes);
+    let larger = smaller.opponent();
+    if sizes[larger as usize] <= sizes[smaller as usize] + 1 {
+        return;
+    }
+
+    // Prefer moving someone who is waiting to respawn so nobody is yanked out of a fight
+    let candidate = players
+        .iter()
+        .filter(|(_, _, team, _)| **team == larger)
+        .max_by_key(|(_, _, _, dead)| dead.is_some());
+
+    if let Some((entity, player, ..)) = candidate {
+        join_team(&mut commands, &mut server, &mut stats, entity, player, smaller);
+    }
+}
+
+fn sync_teams(
+    mut server_events: EventReader<ServerEvent>,
+    players: Query<(&Player, &Team)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for event in server_events.iter() {
+        if let ServerEvent::ClientConnected(id, _) = event {
+            for (player, team) in players.iter() {
+                let message = ServerMessage::PlayerTeam {
+                    id: player.id,
+                    team: *team,
+                };
+                send_message(&mut server, &mut stats, *id, &message);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switch_unbalancing_teams_is_refused() {
+        // 2v2 would become 1v3
+        assert!(!switch_allowed([2, 2], Team::A, Team::B, true));
+        assert!(switch_allowed([2, 2], Team::A, Team::B, false));
+    }
+
+    #[test]
+    fn switch_to_smaller_team_is_allowed() {
+        assert!(switch_allowed([3, 1], Team::A, Team::B, true));
+        assert!(switch_allowed([2, 1], Team::A, Team::B, true));
+        assert!(!switch_allowed([1, 2], Team::A, Team::B, true));
+    }
+}
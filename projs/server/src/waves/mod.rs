@@ -0,0 +1,419 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy_renet::renet::{RenetServer, ServerEvent};
+use blitz_common::{Enemy, EnemyDefs, MatchPhase, MatchWinner, Player, ServerMessage, Team};
+use rand::seq::SliceRandom;
+
+use crate::{
+    config::{ServerSettings, SurvivalRespawn, SurvivalSettings},
+    enemies::{spawn_enemy, EnemyKilled, EnemySpawns},
+    game_modes::{game_mode_active, MatchEnded, MatchTimeUp},
+    health::Dead,
+    networking::{
+        broadcast_message,
+        resources::{NetworkStats, ServerLobby},
+        send_message,
+    },
+    spawning::Spawning,
+};
+
+pub const SURVIVAL: &str = "survival";
+
+#[derive(Debug)]
+enum WavePhase {
+    /// Resting before the next wave
+    Intermission(Timer),
+    /// The wave's enemies are still coming in or alive
+    Fighting,
+}
+
+/// Progress of the current survival match.
+#[derive(Debug, Resource)]
+struct Waves {
+    /// The wave being fought, or the last one cleared during an intermission
+    wave: u32,
+    phase: WavePhase,
+    /// Kinds of the wave's enemies that haven't come in yet, the next one last
+    queued: Vec<String>,
+    /// The wave's enemies still alive
+    alive: HashSet<Entity>,
+    spawn_timer: Timer,
+    lives: Option<u32>,
+    score: u32,
+}
+
+impl Waves {
+    fn new(settings: &SurvivalSettings) -> Self {
+        Self {
+            wave: 0,
+            phase: WavePhase::Intermission(Timer::from_seconds(
+                settings.intermission,
+                TimerMode::Once,
+            )),
+            queued: Vec::new(),
+            alive: HashSet::new(),
+            spawn_timer: Timer::from_seconds(settings.spawn_interval, TimerMode::Repeating),
+            lives: match settings.respawn {
+                SurvivalRespawn::BetweenWaves => None,
+                SurvivalRespawn::SharedLives(lives) => Some(lives),
+            },
+            score: 0,
+        }
+    }
+
+    fn fighting(&self) -> bool {
+        matches!(self.phase, WavePhase::Fighting)
+    }
+
+    fn cleared(&self) -> u32 {
+        match self.phase {
+            WavePhase::Intermission(_) => self.wave,
+            WavePhase::Fighting => self.wave.saturating_sub(1),
+        }
+    }
+
+    fn message(&self) -> ServerMessage {
+        ServerMessage::SurvivalState {
+            wave: self.wave,
+            enemies_remaining: (self.queued.len() + self.alive.len()) as u32,
+            score: self.score,
+            lives: self.lives,
+            next_wave: match &self.phase {
+                WavePhase::Intermission(timer) => Some(timer.remaining_secs()),
+                WavePhase::Fighting => None,
+            },
+        }
+    }
+}
+
+/// Co-op against waves of enemies that grow with every round and with the number of players,
+/// a boss leading every few of them. The match is lost once nobody is left standing.
+pub struct ServerWavesPlugin;
+impl Plugin for ServerWavesPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = &app.world.resource::<ServerSettings>().survival;
+        let waves = Waves::new(settings);
+        app.insert_resource(waves);
+
+        app.add_system(join_survivors.run_if(game_mode_active(SURVIVAL)));
+        app.add_system(
+            start_survival
+                .run_if(game_mode_active(SURVIVAL))
+                .in_schedule(OnEnter(MatchPhase::InProgress)),
+        );
+        app.add_system(
+            release_dead_players
+                .run_if(game_mode_active(SURVIVAL))
+                .in_schedule(OnExit(MatchPhase::InProgress)),
+        );
+        app.add_systems(
+            (
+                count_kills,
+                hold_dead_players,
+                start_next_wave,
+                spawn_wave_enemies,
+                end_wave,
+                check_overrun,
+                time_up,
+                broadcast_survival.run_if(resource_changed::<Waves>()),
+            )
+                .chain()
+                .in_set(OnUpdate(MatchPhase::InProgress))
+                .distributive_run_if(game_mode_active(SURVIVAL)),
+        );
+        app.add_system(sync_survival.run_if(game_mode_active(SURVIVAL)));
+    }
+}
+
+/// Kinds of the enemies making up `wave`, the boss coming in first on boss waves.
+fn wave_enemies(
+    wave: u32,
+    players: usize,
+    settings: &SurvivalSettings,
+    defs: &EnemyDefs,
+) -> Vec<String> {
+    let scale = 1.0 + settings.per_player * players.saturating_sub(1) as f32;
+    let base = settings.first_wave + settings.wave_growth * wave.saturating_sub(1) as usize;
+    let mut count = (base as f32 * scale).round() as usize;
+
+    let boss_wave = settings.boss_every > 0 && wave.is_multiple_of(settings.boss_every);
+    let boss = boss_wave.then(|| defs.get(&settings.boss)).flatten();
+    match boss {
+        // The boss only brings a smaller escort
+        Some(_) => count /= 2,
+        None if boss_wave => {
+            warn!(boss = %settings.boss, "Unknown boss enemy, the wave goes without it");
+        }
+        None => {}
+    }
+
+    let mut rng = rand::thread_rng();
+    let regulars: Vec<_> = defs
+        .enemies
+        .iter()
+        .filter(|def| def.id != settings.boss)
+        .collect();
+
+    let mut queued: Vec<_> = (0..count)
+        .filter_map(|_| regulars.choose(&mut rng))
+        .map(|def| def.id.clone())
+        .collect();
+    queued.extend(boss.map(|boss| boss.id.clone()));
+    queued
+}
+
+/// Survivors all play on one team, the friendly fire setting decides whether they can hurt
+/// each other.
+fn join_survivors(
+    mut commands: Commands,
+    new_players: Query<Entity, (Added<Player>, Without<Team>)>,
+) {
+    for entity in new_players.iter() {
+        commands.entity(entity).insert(Team::A);
+    }
+}
+
+/// Every match starts over from the first wave, without what's left of the last one.
+fn start_survival(
+    mut commands: Commands,
+    enemies: Query<Entity, With<Enemy>>,
+    mut waves: ResMut<Waves>,
+    settings: Res<ServerSettings>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for entity in enemies.iter() {
+        commands.entity(entity).despawn();
+
+        let message = ServerMessage::DespawnEnemy { entity };
+        broadcast_message(&mut server, &mut stats, &message);
+    }
+
+    *waves = Waves::new(&settings.survival);
+}
+
+fn count_kills(
+    mut enemy_killed: EventReader<EnemyKilled>,
+    enemies: Query<(), With<Enemy>>,
+    mut waves: ResMut<Waves>,
+    defs: Res<EnemyDefs>,
+) {
+    for killed in enemy_killed.iter() {
+        if waves.alive.remove(&killed.enemy) {
+            waves.score += defs.get(&killed.kind).map_or(0, |def| def.points);
+        }
+    }
+
+    // Enemies can also go away without being killed, e.g. along with their level
+    if waves.alive.iter().any(|enemy| !enemies.contains(*enemy)) {
+        waves.alive.retain(|enemy| enemies.contains(*enemy));
+    }
+}
+
+/// Deaths during a wave cost the team a life, without any left the dead wait for the wave
+/// to be cleared.
+fn hold_dead_players(mut new_dead: Query<&mut Dead, Added<Dead>>, mut waves: ResMut<Waves>) {
+    if !waves.fighting() {
+        return;
+    }
+
+    for mut dead in new_dead.iter_mut() {
+        match &mut waves.lives {
+            Some(lives) if *lives > 0 => *lives -= 1,
+            _ => dead.respawn.pause(),
+        }
+    }
+}
+
+fn start_next_wave(
+    mut waves: ResMut<Waves>,
+    lobby: Res<ServerLobby>,
+    defs: Res<EnemyDefs>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+) {
+    // Only the wave starting is worth telling the clients, they count the intermission down
+    let WavePhase::Intermission(timer) = &mut waves.bypass_change_detection().phase else {
+        return;
+    };
+    if !timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let wave = waves.wave + 1;
+    let queued = wave_enemies(wave, lobby.players.len(), &settings.survival, &defs);
+    info!(wave, enemies = queued.len(), "Wave started");
+
+    waves.wave = wave;
+    waves.queued = queued;
+    waves.phase = WavePhase::Fighting;
+    waves.spawn_timer.reset();
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn spawn_wave_enemies(
+    mut commands: Commands,
+    mut waves: ResMut<Waves>,
+    players: Query<&Transform, (With<Player>, Without<Dead>, Without<Spawning>)>,
+    spawns: Res<EnemySpawns>,
+    defs: Res<EnemyDefs>,
+    lobby: Res<ServerLobby>,
+    settings: Res<ServerSettings>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    time: Res<Time>,
+) {
+    if !waves.fighting() || waves.queued.is_empty() {
+        return;
+    }
+
+    let spawn_timer = &mut waves.bypass_change_detection().spawn_timer;
+    if !spawn_timer.tick(time.delta()).just_finished()
+        || waves.alive.len() >= settings.survival.max_alive
+    {
+        return;
+    }
+
+    let Some(def) = waves.queued.pop().and_then(|kind| defs.get(&kind)) else {
+        return;
+    };
+
+    let positions: Vec<_> = players
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    let Some(position) = spawns.pick(
+        &def.id,
+        &positions,
+        settings.enemies.min_player_distance,
+        &mut rand::thread_rng(),
+    ) else {
+        warn!(enemy = %def.id, "No enemy spawn on the level, skipping it");
+        return;
+    };
+
+    // Enemies toughen up with every wave, the boss with every player on top
+    let rules = &settings.survival;
+    let mut health = def.health * (1.0 + rules.health_growth * (waves.wave - 1) as f32);
+    if def.id == rules.boss {
+        health *= lobby.players.len().max(1) as f32;
+    }
+
+    let entity = spawn_enemy(
+        &mut commands,
+        &mut server,
+        &mut stats,
+        def,
+        position,
+        health,
+    );
+    waves.alive.insert(entity);
+}
+
+/// Lets everyone held back during the last wave respawn once the match is over, won or lost.
+fn release_dead_players(mut dead_players: Query<&mut Dead>) {
+    for mut dead in dead_players.iter_mut() {
+        dead.respawn.unpause();
+    }
+}
+
+/// Ends the wave once its last enemy is dead, bringing back whoever fell during it.
+fn end_wave(
+    mut waves: ResMut<Waves>,
+    mut dead_players: Query<&mut Dead>,
+    mut match_ended: EventWriter<MatchEnded>,
+    settings: Res<ServerSettings>,
+) {
+    if !waves.fighting() || !waves.queued.is_empty() || !waves.alive.is_empty() {
+        return;
+    }
+
+    let rules = &settings.survival;
+    info!(wave = waves.wave, score = waves.score, "Wave cleared");
+    waves.phase = WavePhase::Intermission(Timer::from_seconds(rules.intermission, TimerMode::Once));
+
+    if rules
+        .waves
+        .is_some_and(|waves_to_win| waves.wave >= waves_to_win)
+    {
+        match_ended.send(MatchEnded {
+            winner: MatchWinner::Survival {
+                waves: waves.wave,
+                survived: true,
+            },
+        });
+        return;
+    }
+
+    for mut dead in dead_players.iter_mut() {
+        dead.respawn.unpause();
+    }
+}
+
+/// The match is lost once every player is dead with nobody left to respawn during the wave.
+fn check_overrun(
+    players: Query<Option<&Dead>, With<Player>>,
+    waves: Res<Waves>,
+    mut match_ended: EventWriter<MatchEnded>,
+) {
+    if !waves.fighting() || players.is_empty() {
+        return;
+    }
+
+    let overrun = players
+        .iter()
+        .all(|dead| dead.is_some_and(|dead| dead.respawn.paused()));
+    if overrun {
+        match_ended.send(MatchEnded {
+            winner: MatchWinner::Survival {
+                waves: waves.cleared(),
+                survived: false,
+            },
+        });
+    }
+}
+
+/// Holding out until the time limit is a win.
+fn time_up(
+    mut match_time_up: EventReader<MatchTimeUp>,
+    waves: Res<Waves>,
+    mut match_ended: EventWriter<MatchEnded>,
+) {
+    if match_time_up.is_empty() {
+        return;
+    }
+    match_time_up.clear();
+
+    match_ended.send(MatchEnded {
+        winner: MatchWinner::Survival {
+            waves: waves.cleared(),
+            survived: true,
+        },
+    });
+}
+
+fn broadcast_survival(
+    waves: Res<Waves>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    broadcast_message(&mut server, &mut stats, &waves.message());
+}
+
+/// Newly connected clients need to know which wave the match is at.
+fn sync_survival(
+    mut server_events: EventReader<ServerEvent>,
+    phase: Res<State<MatchPhase>>,
+    waves: Res<Waves>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    for event in server_events.iter() {
+        if let ServerEvent::ClientConnected(id, _) = event {
+            if phase.0 == MatchPhase::InProgress {
+                send_message(&mut server, &mut stats, *id, &waves.message());
+            }
+        }
+    }
+}
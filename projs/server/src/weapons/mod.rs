@@ -0,0 +1,451 @@
+use std::{f32::consts::FRAC_PI_2, path::Path};
+
+use bevy::{
+    math::{vec2, vec3},
+    prelude::*,
+};
+use bevy_renet::renet::RenetServer;
+use blitz_common::{
+    ExplosionDef, FromPlayer, Player, PlayerCommand, Projectile, ServerMessage, Velocity,
+    WeaponDef, WeaponDefs, WeaponSupply, WEAPONS_FILE,
+};
+use rand::Rng;
+
+use crate::{
+    config::ASSETS_DIR,
+    health::{Dead, PlayerKilled},
+    networking::{broadcast_message, resources::NetworkStats, send_message},
+    status::StatusEffects,
+};
+
+/// A weapon command a player sent, forwarded by the networking plugin.
+#[derive(Debug, Clone, Copy)]
+pub struct WeaponCommand {
+    pub entity: Entity,
+    pub command: PlayerCommand,
+}
+
+/// Hands a weapon to a player and switches to it, e.g. from a pickup.
+#[derive(Debug, Clone)]
+pub struct GiveWeapon {
+    pub entity: Entity,
+    pub weapon: String,
+}
+
+pub struct ServerWeaponsPlugin;
+impl Plugin for ServerWeaponsPlugin {
+    fn build(&self, app: &mut App) {
+        let path = Path::new(ASSETS_DIR).join(WEAPONS_FILE);
+        let defs = WeaponDefs::load(&path).unwrap_or_else(|e| {
+            error!("Failed to load weapons, only the default blaster is available: {e}");
+            WeaponDefs::default()
+        });
+        info!("Loaded {} weapon(s)", defs.weapons.len());
+
+        app.insert_resource(defs);
+        app.add_event::<WeaponCommand>();
+        app.add_event::<GiveWeapon>();
+
+        app.add_systems(
+            (
+                equip_players,
+                reset_loadouts,
+                give_weapons,
+                update_loadouts,
+                handle_weapon_commands,
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Debug)]
+struct WeaponSlot {
+    /// Whether the player carries this weapon at all
+    owned: bool,
+    /// Seconds until the weapon can fire again
+    cooldown: f32,
+    ammo: u32,
+    heat: f32,
+    overheated: bool,
+    /// Seconds until the magazine is full again
+    reload: Option<f32>,
+}
+
+impl WeaponSlot {
+    fn new(def: &WeaponDef) -> Self {
+        let ammo = match def.supply {
+            WeaponSupply::Ammo { capacity, .. } => capacity,
+            _ => 0,
+        };
+
+        Self {
+            owned: !def.pickup_only,
+            cooldown: 0.0,
+            ammo,
+            heat: 0.0,
+            overheated: false,
+            reload: None,
+        }
+    }
+
+    fn can_fire(&self, def: &WeaponDef) -> bool {
+        let supplied = match def.supply {
+            WeaponSupply::Unlimited => true,
+            WeaponSupply::Ammo { .. } => self.reload.is_none() && self.ammo > 0,
+            WeaponSupply::Heat { .. } => !self.overheated,
+        };
+
+        supplied && self.cooldown <= 0.0
+    }
+
+    /// `fire_rate` multiplies the weapon's own, shortening the cooldown.
+    fn fire(&mut self, def: &WeaponDef, fire_rate: f32) {
+        self.cooldown = def.cooldown() / fire_rate.max(f32::EPSILON);
+
+        match def.supply {
+            WeaponSupply::Unlimited => {}
+            WeaponSupply::Ammo { reload_time, .. } => {
+                self.ammo = self.ammo.saturating_sub(1);
+                if self.ammo == 0 {
+                    self.reload = Some(reload_time);
+                }
+            }
+            WeaponSupply::Heat { per_shot, .. } => {
+                self.heat = (self.heat + per_shot).min(1.0);
+                self.overheated = self.heat >= 1.0;
+            }
+        }
+    }
+
+    /// Returns true when the weapon became usable again.
+    fn update(&mut self, def: &WeaponDef, delta: f32) -> bool {
+        self.cooldown = (self.cooldown - delta).max(0.0);
+
+        match def.supply {
+            WeaponSupply::Unlimited => false,
+            WeaponSupply::Ammo { capacity, .. } => {
+                let Some(reload) = &mut self.reload else {
+                    return false;
+                };
+
+                *reload -= delta;
+                if *reload > 0.0 {
+                    return false;
+                }
+
+                self.reload = None;
+                self.ammo = capacity;
+                true
+            }
+            WeaponSupply::Heat { cooling, .. } => {
+                self.heat = (self.heat - cooling * delta).max(0.0);
+                if self.overheated && self.heat <= 0.0 {
+                    self.overheated = false;
+                    return true;
+                }
+                false
+            }
+        }
+    }
+}
+
+/// The weapons a player carries, one slot per weapon definition.
+#[derive(Debug, Component)]
+pub struct Loadout {
+    slots: Vec<WeaponSlot>,
+    active: usize,
+}
+
+impl Loadout {
+    fn new(defs: &WeaponDefs) -> Self {
+        let mut slots: Vec<_> = defs.weapons.iter().map(WeaponSlot::new).collect();
+
+        // Nobody should spawn unarmed because every weapon is marked as a pickup
+        if !slots.iter().any(|slot| slot.owned) {
+            slots[0].owned = true;
+        }
+
+        let active = slots.iter().position(|slot| slot.owned).unwrap_or_default();
+        Self { slots, active }
+    }
+
+    fn select(&mut self, index: usize) -> bool {
+        let owned = self.slots.get(index).is_some_and(|slot| slot.owned);
+        if !owned || index == self.active {
+            return false;
+        }
+
+        self.active = index;
+        true
+    }
+
+    /// Selects the closest owned weapon in the given direction, wrapping around.
+    fn cycle(&mut self, forward: bool) -> bool {
+        let count = self.slots.len();
+        let next = (1..count)
+            .map(|step| match forward {
+                true => (self.active + step) % count,
+                false => (self.active + count - step) % count,
+            })
+            .find(|&index| self.slots[index].owned);
+
+        match next {
+            Some(index) => self.select(index),
+            None => false,
+        }
+    }
+
+    /// Adds the weapon with a full magazine and switches to it.
+    fn give(&mut self, def: &WeaponDef, index: usize) {
+        self.slots[index] = WeaponSlot::new(def);
+        self.slots[index].owned = true;
+        self.active = index;
+    }
+}
+
+/// Fires one volley of `def` from `shooter`, who gets the credit for any hit.
+/// `velocity` is the shooter's, the weapon decides how much of it its projectiles keep.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_projectiles(
+    commands: &mut Commands,
+    server: &mut RenetServer,
+    stats: &mut NetworkStats,
+    rng: &mut impl Rng,
+    shooter: Entity,
+    transform: &Transform,
+    velocity: Vec2,
+    def: &WeaponDef,
+    damage: f32,
+) {
+    // Boosts and charging scale the blast along with the hit itself
+    let explosion = def.explosion.map(|explosion| ExplosionDef {
+        damage: match def.damage > 0.0 {
+            true => explosion.damage * damage / def.damage,
+            false => explosion.damage,
+        },
+        ..explosion
+    });
+
+    let half_spread = def.spread.to_radians() / 2.0;
+    for _ in 0..def.pellets {
+        let offset = rng.gen_range(-half_spread..=half_spread);
+        let aim = (transform.rotation * Quat::from_rotation_z(offset) * Vec3::Y).truncate();
+        let velocity = aim * def.projectile_speed + velocity * def.inherit_velocity;
+        let rotation = Quat::from_rotation_z(velocity.y.atan2(velocity.x) - FRAC_PI_2);
+
+        let projectile_entity = commands
+            .spawn(SpriteBundle {
+                transform: Transform {
+                    translation: transform.translation,
+                    scale: vec3(0.5, 0.5, 1.0),
+                    rotation,
+                },
+                ..Default::default()
+            })
+            .insert(Projectile {
+                duration: Timer::from_seconds(def.lifetime, TimerMode::Once),
+                damage,
+                bounces: def.bounces,
+                pierce: def.pierce,
+                status: def.status.clone(),
+                explosion,
+                hit: Vec::new(),
+            })
+            .insert(Velocity(velocity))
+            .insert(def.collider)
+            .insert(FromPlayer { entity: shooter })
+            .id();
+        debug!(
+            network_id = ?projectile_entity,
+            weapon = %def.id,
+            "Spawned projectile"
+        );
+
+        let message = ServerMessage::SpawnProjectile {
+            entity: projectile_entity,
+            transform: vec2(transform.translation.x, transform.translation.y),
+            rotation,
+            weapon: def.id.clone(),
+        };
+        broadcast_message(server, stats, &message);
+    }
+}
+
+fn send_status(
+    server: &mut RenetServer,
+    stats: &mut NetworkStats,
+    client_id: u64,
+    defs: &WeaponDefs,
+    loadout: &Loadout,
+) {
+    let def = &defs.weapons[loadout.active];
+    let slot = &loadout.slots[loadout.active];
+
+    let message = ServerMessage::WeaponStatus {
+        weapon: def.id.clone(),
+        ammo: matches!(def.supply, WeaponSupply::Ammo { .. }).then_some(slot.ammo),
+        heat: slot.heat,
+        reloading: slot.reload.is_some(),
+    };
+    send_message(server, stats, client_id, &message);
+}
+
+fn equip_players(
+    mut commands: Commands,
+    new_players: Query<(Entity, &Player), Added<Player>>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    defs: Res<WeaponDefs>,
+) {
+    for (entity, player) in new_players.iter() {
+        let loadout = Loadout::new(&defs);
+        send_status(&mut server, &mut stats, player.id, &defs, &loadout);
+        commands.entity(entity).insert(loadout);
+    }
+}
+
+/// Weapons picked up are lost on death.
+fn reset_loadouts(
+    mut player_killed: EventReader<PlayerKilled>,
+    mut loadouts: Query<(&Player, &mut Loadout)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    defs: Res<WeaponDefs>,
+) {
+    for killed in player_killed.iter() {
+        let Ok((player, mut loadout)) = loadouts.get_mut(killed.victim) else {
+            continue;
+        };
+
+        *loadout = Loadout::new(&defs);
+        send_status(&mut server, &mut stats, player.id, &defs, &loadout);
+    }
+}
+
+fn give_weapons(
+    mut give_weapon: EventReader<GiveWeapon>,
+    mut loadouts: Query<(&Player, &mut Loadout)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    defs: Res<WeaponDefs>,
+) {
+    for request in give_weapon.iter() {
+        let Ok((player, mut loadout)) = loadouts.get_mut(request.entity) else {
+            continue;
+        };
+
+        let Some(index) = defs.weapons.iter().position(|def| def.id == request.weapon) else {
+            warn!(weapon = %request.weapon, "Tried to give an unknown weapon");
+            continue;
+        };
+
+        loadout.give(&defs.weapons[index], index);
+        send_status(&mut server, &mut stats, player.id, &defs, &loadout);
+    }
+}
+
+fn update_loadouts(
+    mut loadouts: Query<(&Player, &mut Loadout)>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    defs: Res<WeaponDefs>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+
+    for (player, mut loadout) in loadouts.iter_mut() {
+        let active = loadout.active;
+        let mut ready = false;
+
+        for (index, (slot, def)) in loadout.slots.iter_mut().zip(&defs.weapons).enumerate() {
+            ready |= slot.update(def, delta) && index == active;
+        }
+
+        if ready {
+            send_status(&mut server, &mut stats, player.id, &defs, &loadout);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn handle_weapon_commands(
+    mut commands: Commands,
+    mut weapon_commands: EventReader<WeaponCommand>,
+    mut players: Query<(
+        &Player,
+        &Transform,
+        &Velocity,
+        &mut Loadout,
+        Option<&StatusEffects>,
+        Option<&Dead>,
+    )>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    defs: Res<WeaponDefs>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for request in weapon_commands.iter() {
+        let Ok((player, transform, velocity, mut loadout, effects, dead)) =
+            players.get_mut(request.entity) else {
+            continue;
+        };
+
+        let changed = match request.command {
+            PlayerCommand::BasicAttack => {
+                let def = &defs.weapons[loadout.active];
+                let active = loadout.active;
+                let slot = &mut loadout.slots[active];
+                if dead.is_some() || !slot.can_fire(def) {
+                    continue;
+                }
+                let fire_rate = effects.map_or(1.0, StatusEffects::fire_rate_multiplier);
+                slot.fire(def, fire_rate);
+
+                let damage = def.damage * effects.map_or(1.0, StatusEffects::damage_multiplier);
+
+                spawn_projectiles(
+                    &mut commands,
+                    &mut server,
+                    &mut stats,
+                    &mut rng,
+                    request.entity,
+                    transform,
+                    velocity.0,
+                    def,
+                    damage,
+                );
+
+                // Unlimited weapons have no state worth telling the shooter about
+                def.supply != WeaponSupply::Unlimited
+            }
+            PlayerCommand::SelectWeapon(index) => loadout.select(index as usize),
+            PlayerCommand::NextWeapon => loadout.cycle(true),
+            PlayerCommand::PreviousWeapon => loadout.cycle(false),
+            PlayerCommand::Reload => {
+                let def = &defs.weapons[loadout.active];
+                let active = loadout.active;
+                let slot = &mut loadout.slots[active];
+
+                match def.supply {
+                    WeaponSupply::Ammo {
+                        capacity,
+                        reload_time,
+                    } if slot.reload.is_none() && slot.ammo < capacity => {
+                        slot.reload = Some(reload_time);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            PlayerCommand::ChangeTeam(_)
+            | PlayerCommand::ActivateAbility(_)
+            | PlayerCommand::ReleaseAbility(_) => false,
+        };
+
+        if changed {
+            send_status(&mut server, &mut stats, player.id, &defs, &loadout);
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    collections::{HashMap, HashSet},
+    f32::consts::FRAC_PI_2,
+};
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use blitz_common::{
+    Collider, MapData, Player, Projectile, ServerMessage, Velocity, Zone, ZoneKind,
+};
+
+use crate::{
+    broadphase::{update_broadphase, Broadphase},
+    explosions::Knockback,
+    health::{DamageEvent, Dead},
+    map::CurrentLevel,
+    networking::{broadcast_message, resources::NetworkStats},
+    spawning::Spawning,
+    status::ApplyStatus,
+};
+
+/// Seconds between two hits of a damage zone, and between two refreshes of a slow field.
+const ZONE_TICK: f32 = 0.5;
+/// Seconds before something that came out of a teleporter can be teleported again,
+/// otherwise it would bounce straight back from the other end.
+const TELEPORT_COOLDOWN: f32 = 1.0;
+
+/// Server side state of one of the current level's zones.
+#[derive(Debug, Component)]
+struct ZoneState {
+    /// Position among the level's zones, which is how clients tell them apart
+    index: u16,
+    /// Center of the linked teleporter
+    destination: Option<Vec2>,
+    /// Ships and projectiles inside as of last frame, pads and teleporters go off on entering
+    inside: HashSet<Entity>,
+    /// Paces damage zones and slow fields
+    tick: Timer,
+}
+
+/// Ships and projectiles that just came out of a teleporter.
+#[derive(Debug, Default, Resource)]
+struct Teleported(HashMap<Entity, Timer>);
+
+pub struct ServerZonesPlugin;
+impl Plugin for ServerZonesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Teleported>();
+
+        app.add_system(spawn_zones.run_if(resource_changed::<CurrentLevel>()));
+        app.add_systems((trigger_zones, tick_zones).chain().after(update_broadphase));
+    }
+}
+
+/// Replaces the zones of the previous level with the ones placed on the current one.
+fn spawn_zones(
+    mut commands: Commands,
+    zones: Query<Entity, With<Zone>>,
+    mut teleported: ResMut<Teleported>,
+    current: Res<CurrentLevel>,
+    map: Res<MapData>,
+) {
+    for entity in zones.iter() {
+        commands.entity(entity).despawn();
+    }
+    teleported.0.clear();
+
+    let Some(level) = map.level(&current.identifier) else {
+        return;
+    };
+
+    for (index, zone) in level.zones().enumerate() {
+        let zone = match zone {
+            Ok(zone) => zone,
+            Err(e) => {
+                warn!(level = %level.identifier, index, "Invalid zone: {e}");
+                continue;
+            }
+        };
+
+        let destination = match &zone.kind {
+            ZoneKind::Teleporter { link } => {
+                let destination = level
+                    .teleporter_destination(index)
+                    .and_then(|destination| level.zones().nth(destination)?.ok())
+                    .map(|destination| destination.position);
+                if destination.is_none() {
+                    warn!(level = %level.identifier, %link, "Teleporter without a counterpart");
+                }
+                destination
+            }
+            _ => None,
+        };
+
+        debug!(index, kind = ?zone.kind, "Spawned zone");
+        commands.spawn((
+            zone,
+            ZoneState {
+                index: index as u16,
+                destination,
+                inside: HashSet::new(),
+                tick: Timer::from_seconds(ZONE_TICK, TimerMode::Repeating),
+            },
+        ));
+    }
+}
+
+/// Sets off slow fields, boost pads and teleporters for whatever entered them since last frame.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn trigger_zones(
+    mut zones: Query<(&Zone, &mut ZoneState)>,
+    mut players: Query<
+        (Entity, &mut Transform, &Collider),
+        (With<Player>, Without<Dead>, Without<Spawning>),
+    >,
+    mut projectiles: Query<
+        (Entity, &mut Transform, &mut Velocity),
+        (With<Projectile>, Without<Player>),
+    >,
+    mut teleported: ResMut<Teleported>,
+    mut apply_status: EventWriter<ApplyStatus>,
+    mut knockbacks: EventWriter<Knockback>,
+    mut broadphase: ResMut<Broadphase>,
+    mut server: ResMut<RenetServer>,
+    mut stats: ResMut<NetworkStats>,
+    time: Res<Time>,
+) {
+    teleported
+        .0
+        .retain(|_, cooldown| !cooldown.tick(time.delta()).finished());
+
+    for (zone, mut state) in zones.iter_mut() {
+        let mut inside = HashSet::new();
+
+        let candidates = broadphase.ships_near(zone.position, zone.size);
+        let mut ships = players.iter_many_mut(candidates);
+        while let Some((entity, mut transform, collider)) = ships.fetch_next() {
+            if !zone.contains(transform.translation.truncate()) {
+                continue;
+            }
+
+            inside.insert(entity);
+            if state.inside.contains(&entity) {
+                continue;
+            }
+
+            match &zone.kind {
+                ZoneKind::Damage { .. } => {}
+                ZoneKind::Slow { status } => apply_status.send(ApplyStatus {
+                    target: entity,
+                    status: status.clone(),
+                    source: None,
+                }),
+                ZoneKind::BoostPad { impulse } => knockbacks.send(Knockback {
+                    target: entity,
+                    velocity: *impulse,
+                }),
+                ZoneKind::Teleporter { .. } => {
+                    let Some(destination) = state.destination else {
+                        continue;
+                    };
+                    if teleported.0.contains_key(&entity) {
+                        continue;
+                    }
+
+                    transform.translation.x = destination.x;
+                    transform.translation.y = destination.y;
+                    broadphase.moved(entity, destination, collider.bounds(&transform));
+                    teleported.0.insert(
+                        entity,
+                        Timer::from_seconds(TELEPORT_COOLDOWN, TimerMode::Once),
+                    );
+                }
+            }
+
+            debug!(zone = state.index, player = ?entity, "Zone triggered");
+
+            let message = ServerMessage::ZoneTriggered {
+                zone: state.index,
+                entity,
+            };
+            broadcast_message(&mut server, &mut stats, &message);
+        }
+
+        // Projectiles only care about pads and teleporters, and nobody needs to hear about them
+        for (entity, mut transform, mut velocity) in projectiles.iter_mut() {
+            if !zone.contains(transform.translation.truncate()) {
+                continue;
+            }
+
+            inside.insert(entity);
+            if state.inside.contains(&entity) {
+                continue;
+            }
+
+            match &zone.kind {
+                ZoneKind::BoostPad { impulse } => {
+                    velocity.0 += *impulse;
+                    transform.rotation =
+                        Quat::from_rotation_z(velocity.0.y.atan2(velocity.0.x) - FRAC_PI_2);
+                }
+                ZoneKind::Teleporter { .. } => {
+                    let Some(destination) = state.destination else {
+                        continue;
+                    };
+                    if teleported.0.contains_key(&entity) {
+                        continue;
+                    }
+
+                    transform.translation.x = destination.x;
+                    transform.translation.y = destination.y;
+                    teleported.0.insert(
+                        entity,
+                        Timer::from_seconds(TELEPORT_COOLDOWN, TimerMode::Once),
+                    );
+                }
+                ZoneKind::Damage { .. } | ZoneKind::Slow { .. } => {}
+            }
+        }
+
+        state.inside = inside;
+    }
+}
+
+/// Hurts players standing in damage zones and keeps slow fields' effect on them.
+fn tick_zones(
+    mut zones: Query<(&Zone, &mut ZoneState)>,
+    players: Query<(), (With<Player>, Without<Dead>)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut apply_status: EventWriter<ApplyStatus>,
+    time: Res<Time>,
+) {
+    for (zone, mut state) in zones.iter_mut() {
+        if !state.tick.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let targets = state
+            .inside
+            .iter()
+            .copied()
+            .filter(|entity| players.contains(*entity));
+
+        match &zone.kind {
+            ZoneKind::Damage { per_second } => {
+                for target in targets {
+                    damage_events.send(DamageEvent {
+                        target,
+                        amount: per_second * ZONE_TICK,
+                        source: None,
+                    });
+                }
+            }
+            ZoneKind::Slow { status } => {
+                for target in targets {
+                    apply_status.send(ApplyStatus {
+                        target,
+                        status: status.clone(),
+                        source: None,
+                    });
+                }
+            }
+            ZoneKind::BoostPad { .. } | ZoneKind::Teleporter { .. } => {}
+        }
+    }
+}
@@ -114,6 +114,29 @@ fn build() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+fn bench() -> Result<(), Box<dyn Error>> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+
+    let status = Command::new(cargo)
+        .current_dir(project_root())
+        .args([
+            "run",
+            "--release",
+            "--package",
+            "blitz-common",
+            "--example",
+            "broadphase",
+        ])
+        .args(env::args().skip(2))
+        .status()?;
+
+    if !status.success() {
+        Err("broadphase benchmark failed")?;
+    }
+
+    Ok(())
+}
+
 fn run_server_client(n: usize) -> Result<(), Box<dyn Error>> {
     build()?;
     run(n)?;
@@ -133,6 +156,7 @@ fn try_main() -> Result<(), Box<dyn Error>> {
                 run_server_client(1)?;
             }
         }
+        Some("bench") => bench()?,
         _ => print_help(),
     }
     Ok(())
@@ -143,6 +167,7 @@ fn print_help() {
         "Tasks:
 blitz               builds and runs Blitz with a Server and Client
 blitz X             builds and runs Blitz with a Server and X number of Client
+bench [SHIPS]       runs the broadphase benchmark against hundreds of projectiles
 "
     )
 }